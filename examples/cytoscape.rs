@@ -0,0 +1,65 @@
+//! Writes a self-contained HTML file, with the viewer in `tools/cytoscape`, for a graph written by
+//! `half_edge_graph::debug::write_cytoscape`.
+//!
+//! ```text
+//! cargo run --example cytoscape -- graph.json graph.html
+//! ```
+//!
+//! The HTML is written to stdout if no output file is given.
+use std::fs;
+use std::io::Write;
+
+const SCRIPTS: [&str; 3] = [
+    include_str!("../tools/cytoscape/cytoscape.min.js"),
+    include_str!("../tools/cytoscape/jquery.min.js"),
+    include_str!("../tools/cytoscape/victor.min.js"),
+];
+const VIEWER: &str = include_str!("../tools/cytoscape/script.js");
+
+/// Escapes every `</` so the content can't end the script tag it is embedded in, whatever the case
+/// of the tag name. `</` only occurs in strings in the scripts and the json, where `<\/` is the same.
+fn script(content: &str) -> String {
+    format!("<script>\n{}\n</script>\n", content.replace("</", "<\\/"))
+}
+
+/// Escapes text, like the file name in the title, for the content of a HTML element
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn main() -> std::io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let input = match args.next() {
+        Some(input) => input,
+        None => {
+            eprintln!("usage: cytoscape <graph.json> [output.html]");
+            std::process::exit(1);
+        }
+    };
+    let json = fs::read_to_string(&input)?;
+
+    let mut html = String::new();
+    html.push_str("<!doctype html>\n<html lang=\"en\">\n<head>\n");
+    html.push_str("<meta charset=\"utf-8\">\n");
+    html.push_str(&format!(
+        "<title>{} - Cytoscape</title>\n",
+        escape_html(&input)
+    ));
+    for content in SCRIPTS.iter() {
+        html.push_str(&script(content));
+    }
+    html.push_str(&script(&format!("var data = {};", json)));
+    html.push_str("<style>\nhtml, body, #graph { width: 100%; height: 100%; }\n</style>\n");
+    html.push_str("</head>\n<body>\n<div id=\"graph\"></div>\n");
+    html.push_str(&script(VIEWER));
+    html.push_str("</body>\n</html>\n");
+
+    match args.next() {
+        Some(output) => fs::write(output, html),
+        None => std::io::stdout().write_all(html.as_bytes()),
+    }
+}
@@ -0,0 +1,236 @@
+//! Writes the initial corpus of the fuzz targets, built from the meshes used in the tests.
+//!
+//! `cargo run --example seed_corpus` from the `fuzz` directory, then
+//! `cargo fuzz run operations` or `cargo fuzz run multigraph`.
+//! Each seed is also run once, so this doubles as a quick check without a fuzzer.
+use std::fs;
+use std::path::Path;
+
+use half_edge_graph::{primitives, HalfEdgeGraph};
+use half_edge_graph_fuzz::{decode, encode, run, Multigraph, Operation};
+
+type Mesh = HalfEdgeGraph<()>;
+
+/// The operations that builds the faces of the graph, with `polygon` creating each face
+fn from_graph(graph: &Mesh, polygon: fn(Vec<u16>) -> Operation) -> Vec<Operation> {
+    let vertices: Vec<_> = graph.iter_vertices().map(|v| v.handle()).collect();
+    let index = |handle| vertices.iter().position(|v| *v == handle).unwrap() as u16;
+
+    let mut operations = vec![Operation::NewVertex; vertices.len()];
+    for face in graph.iter_faces() {
+        operations.push(polygon(
+            face.vertices().map(|v| index(v.handle())).collect(),
+        ));
+    }
+    operations
+}
+
+/// All faces at once through [HalfEdgeGraph::add_polygons]
+fn polygons(graph: &Mesh) -> Vec<Operation> {
+    let faces = from_graph(graph, Operation::NewFace);
+    let vertices = graph.iter_vertices().count();
+    let polygons = faces[vertices..]
+        .iter()
+        .map(|operation| match operation {
+            Operation::NewFace(polygon) => polygon.clone(),
+            _ => unreachable!(),
+        })
+        .collect();
+    let mut operations = faces[..vertices].to_vec();
+    operations.push(Operation::AddPolygons(polygons));
+    operations
+}
+
+fn vertices(count: usize) -> Vec<Operation> {
+    vec![Operation::NewVertex; count]
+}
+
+fn main() {
+    let position = |_| ();
+    let meshes: Vec<(&str, Mesh)> = vec![
+        ("grid", primitives::grid(3, 3, position)),
+        ("disc", primitives::disc(6, position)),
+        ("cube", primitives::cube(position)),
+        ("prism", primitives::prism(5, position)),
+        ("cylinder", primitives::cylinder(6, 2, position)),
+        ("cone", primitives::cone(5, position)),
+        ("uv_sphere", primitives::uv_sphere(6, 4, position)),
+        ("icosphere", primitives::icosphere(1, position)),
+        ("torus", primitives::torus(6, 4, 2., 0.5, position)),
+    ];
+
+    let mut seeds = Vec::new();
+    for (name, mesh) in &meshes {
+        let mut faces = from_graph(mesh, Operation::NewFace);
+        seeds.push((format!("{}_faces", name), faces.clone()));
+        seeds.push((format!("{}_polygons", name), polygons(mesh)));
+
+        faces.extend(vec![
+            Operation::ReverseFace(0),
+            Operation::ReverseComponent(1),
+            Operation::Undo,
+            Operation::Undo,
+            Operation::Redo,
+        ]);
+        seeds.push((format!("{}_edits", name), faces));
+    }
+
+    // The meshes built by hand in the tests
+    seeds.push((
+        "mesh_1".into(),
+        [
+            vertices(6),
+            vec![
+                Operation::NewFace(vec![0, 1, 2, 3]),
+                Operation::NewFace(vec![1, 0, 4, 5]),
+            ],
+        ]
+        .concat(),
+    ));
+    seeds.push((
+        "non_manifold_vertices".into(),
+        [
+            vertices(6),
+            vec![
+                Operation::NewFace(vec![0, 1, 2]),
+                Operation::NewFace(vec![0, 3, 4]),
+                Operation::NewEdge(0, 5),
+                Operation::SplitNonManifoldVertices,
+                Operation::Undo,
+            ],
+        ]
+        .concat(),
+    ));
+    seeds.push((
+        "glued_polygons".into(),
+        [
+            vertices(5),
+            vec![Operation::AddPolygons(vec![
+                vec![0, 1, 2],
+                vec![1, 0, 3],
+                vec![0, 1, 4],
+            ])],
+        ]
+        .concat(),
+    ));
+    seeds.push((
+        "mobius_strip".into(),
+        [
+            vertices(10),
+            vec![Operation::AddOrientedPolygons(vec![
+                vec![0, 1, 6, 5],
+                vec![1, 2, 7, 6],
+                vec![2, 3, 8, 7],
+                vec![3, 4, 9, 8],
+                vec![4, 5, 0, 9],
+            ])],
+        ]
+        .concat(),
+    ));
+    seeds.push((
+        "face_visiting_vertex_twice".into(),
+        [
+            vertices(5),
+            vec![
+                Operation::NewEdge(0, 3),
+                Operation::NewEdge(0, 4),
+                Operation::NewFace(vec![0, 1, 0, 2]),
+            ],
+        ]
+        .concat(),
+    ));
+    seeds.push((
+        "self_loops".into(),
+        [
+            vertices(3),
+            vec![
+                Operation::NewEdge(0, 0),
+                Operation::NewEdge(1, 2),
+                Operation::NewEdge(1, 2),
+                Operation::NewEdge(1, 1),
+                Operation::NewFace(vec![1, 2]),
+            ],
+        ]
+        .concat(),
+    ));
+
+    seeds.push((
+        "face_from_half_edges".into(),
+        [
+            vertices(3),
+            vec![
+                Operation::NewEdge(0, 1),
+                Operation::NewEdge(1, 2),
+                Operation::NewEdge(1, 2),
+                Operation::NewEdge(2, 0),
+                Operation::NewFaceFromHalfEdges(vec![2, 4]),
+                Operation::NewFaceFromHalfEdges(vec![0, 2, 6]),
+                Operation::NewFaceFromHalfEdges(vec![1, 7, 3]),
+            ],
+        ]
+        .concat(),
+    ));
+    seeds.push((
+        "embedded_edges".into(),
+        [
+            vertices(6),
+            vec![
+                Operation::NewEdgeEmbedded(0, 1, 3),
+                Operation::NewEdgeEmbedded(1, 2, 3),
+                Operation::NewEdgeEmbedded(2, 0, 3),
+                Operation::NewEdgeEmbedded(0, 3, 3),
+                Operation::NewEdgeEmbedded(3, 4, 3),
+                Operation::NewEdgeEmbedded(4, 5, 3),
+                Operation::NewFace(vec![0, 1, 2]),
+                Operation::NewEdgeEmbedded(5, 1, 3),
+            ],
+        ]
+        .concat(),
+    ));
+    let k5: Vec<_> = (0..5)
+        .flat_map(|i| (i + 1..5).map(move |j| Operation::NewEdge(i, j)))
+        .collect();
+    seeds.push((
+        "embed_planar".into(),
+        [
+            vertices(5),
+            k5[..9].to_vec(),
+            vec![
+                Operation::EmbedPlanar(7),
+                Operation::Undo,
+                Operation::EmbedPlanar(0),
+            ],
+        ]
+        .concat(),
+    ));
+    seeds.push((
+        "planar_embedding".into(),
+        [
+            vertices(5),
+            k5[..9].to_vec(),
+            vec![
+                Operation::PlanarEmbedding,
+                Operation::Undo,
+                k5[9].clone(),
+                Operation::PlanarEmbedding,
+            ],
+        ]
+        .concat(),
+    ));
+
+    for target in &["operations", "multigraph"] {
+        let directory = Path::new("corpus").join(target);
+        fs::create_dir_all(&directory).expect("failed to create corpus directory");
+
+        for (name, operations) in &seeds {
+            let bytes = encode(operations);
+            assert_eq!(&decode(&bytes), operations, "{} doesn't round trip", name);
+            match *target {
+                "operations" => run::<()>(operations),
+                _ => run::<Multigraph>(operations),
+            }
+            fs::write(directory.join(name), bytes).expect("failed to write seed");
+        }
+    }
+    println!("wrote {} seeds for each target", seeds.len());
+}
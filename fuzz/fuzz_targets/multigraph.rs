@@ -0,0 +1,8 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use half_edge_graph_fuzz::Multigraph;
+
+fuzz_target!(|data: &[u8]| {
+    half_edge_graph_fuzz::run::<Multigraph>(&half_edge_graph_fuzz::decode(data));
+});
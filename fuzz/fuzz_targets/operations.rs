@@ -0,0 +1,6 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    half_edge_graph_fuzz::run::<()>(&half_edge_graph_fuzz::decode(data));
+});
@@ -0,0 +1,314 @@
+//! Decoding of fuzzer input into sequences of graph operations
+//!
+//! Each operation starts with a opcode byte, followed by its arguments. Indices are
+//! little endian `u16`s and select vertices, half edges or faces in iteration order, modulo the
+//! number of items.
+//! Lists are prefixed with their length as a byte. Input that ends in the middle of a operation
+//! drops that operation, so any byte stream decodes.
+//!
+//! | Opcode | Operation                 | Arguments                            |
+//! |--------|---------------------------|--------------------------------------|
+//! | 0      | new_vertex                |                                      |
+//! | 1      | new_edge                  | vertex, vertex                       |
+//! | 2      | new_face                  | list of vertices                     |
+//! | 3      | reverse_face              | face                                 |
+//! | 4      | reverse_component         | face                                 |
+//! | 5      | split_non_manifold_vertices |                                    |
+//! | 6      | undo                      |                                      |
+//! | 7      | redo                      |                                      |
+//! | 8      | add_polygons              | list of lists of vertices            |
+//! | 9      | add_oriented_polygons     | list of lists of vertices            |
+//! | 10     | new_face_from_half_edges  | list of half edges                   |
+//! | 11     | new_edge_embedded         | vertex, vertex, layout               |
+//! | 12     | embed_planar              | layout                               |
+//! | 13     | planar_embedding          |                                      |
+//!
+//! Opcodes are taken modulo the number of operations. The `layout` is a `u16` that places
+//! the vertices on a 16x16 grid, see [position].
+use std::collections::HashMap;
+
+use half_edge_graph::{Data, HalfEdgeGraph, VertexFn};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    NewVertex,
+    NewEdge(u16, u16),
+    NewFace(Vec<u16>),
+    ReverseFace(u16),
+    ReverseComponent(u16),
+    SplitNonManifoldVertices,
+    Undo,
+    Redo,
+    AddPolygons(Vec<Vec<u16>>),
+    AddOrientedPolygons(Vec<Vec<u16>>),
+    NewFaceFromHalfEdges(Vec<u16>),
+    NewEdgeEmbedded(u16, u16, u16),
+    EmbedPlanar(u16),
+    PlanarEmbedding,
+}
+
+const OPCODES: u8 = 14;
+
+/// The position of the vertex with `index` in iteration order, for the planar operations.
+///
+/// `layout` shuffles the vertices on a 16x16 grid, the first 256 vertices get different
+/// positions, but they can be collinear and the edges can cross.
+pub fn position(layout: u16, index: usize) -> [f64; 2] {
+    let cell = (index as u64 * (2 * layout as u64 + 1)) % 256;
+    [(cell % 16) as f64, (cell / 16) as f64]
+}
+
+/// A graph that allows parallel edges and self loops
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Multigraph;
+
+impl Data for Multigraph {
+    type Face = ();
+    type Edge = ();
+    type HalfEdge = ();
+    type Vertex = ();
+
+    const ALLOW_PARALLEL_EDGES: bool = true;
+    const ALLOW_SELF_LOOPS: bool = true;
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Option<u8> {
+        let (first, rest) = self.bytes.split_first()?;
+        self.bytes = rest;
+        Some(*first)
+    }
+
+    fn index(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes([self.byte()?, self.byte()?]))
+    }
+
+    fn indices(&mut self) -> Option<Vec<u16>> {
+        let len = self.byte()?;
+        (0..len).map(|_| self.index()).collect()
+    }
+
+    fn polygons(&mut self) -> Option<Vec<Vec<u16>>> {
+        let len = self.byte()?;
+        (0..len).map(|_| self.indices()).collect()
+    }
+
+    fn operation(&mut self) -> Option<Operation> {
+        Some(match self.byte()? % OPCODES {
+            0 => Operation::NewVertex,
+            1 => Operation::NewEdge(self.index()?, self.index()?),
+            2 => Operation::NewFace(self.indices()?),
+            3 => Operation::ReverseFace(self.index()?),
+            4 => Operation::ReverseComponent(self.index()?),
+            5 => Operation::SplitNonManifoldVertices,
+            6 => Operation::Undo,
+            7 => Operation::Redo,
+            8 => Operation::AddPolygons(self.polygons()?),
+            9 => Operation::AddOrientedPolygons(self.polygons()?),
+            10 => Operation::NewFaceFromHalfEdges(self.indices()?),
+            11 => Operation::NewEdgeEmbedded(self.index()?, self.index()?, self.index()?),
+            12 => Operation::EmbedPlanar(self.index()?),
+            _ => Operation::PlanarEmbedding,
+        })
+    }
+}
+
+/// Decodes a byte stream into operations
+pub fn decode(bytes: &[u8]) -> Vec<Operation> {
+    let mut reader = Reader { bytes };
+    std::iter::from_fn(|| reader.operation()).collect()
+}
+
+/// Encodes operations so that [decode] returns them, lists longer than 255 items are truncated.
+pub fn encode(operations: &[Operation]) -> Vec<u8> {
+    fn indices(bytes: &mut Vec<u8>, indices: &[u16]) {
+        let indices = &indices[..indices.len().min(255)];
+        bytes.push(indices.len() as u8);
+        for index in indices {
+            bytes.extend_from_slice(&index.to_le_bytes());
+        }
+    }
+    fn polygons(bytes: &mut Vec<u8>, polygons: &[Vec<u16>]) {
+        let polygons = &polygons[..polygons.len().min(255)];
+        bytes.push(polygons.len() as u8);
+        for polygon in polygons {
+            indices(bytes, polygon);
+        }
+    }
+
+    let mut bytes = Vec::new();
+    for operation in operations {
+        match operation {
+            Operation::NewVertex => bytes.push(0),
+            Operation::NewEdge(a, b) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&a.to_le_bytes());
+                bytes.extend_from_slice(&b.to_le_bytes());
+            }
+            Operation::NewFace(vertices) => {
+                bytes.push(2);
+                indices(&mut bytes, vertices);
+            }
+            Operation::ReverseFace(face) => {
+                bytes.push(3);
+                bytes.extend_from_slice(&face.to_le_bytes());
+            }
+            Operation::ReverseComponent(face) => {
+                bytes.push(4);
+                bytes.extend_from_slice(&face.to_le_bytes());
+            }
+            Operation::SplitNonManifoldVertices => bytes.push(5),
+            Operation::Undo => bytes.push(6),
+            Operation::Redo => bytes.push(7),
+            Operation::AddPolygons(list) => {
+                bytes.push(8);
+                polygons(&mut bytes, list);
+            }
+            Operation::AddOrientedPolygons(list) => {
+                bytes.push(9);
+                polygons(&mut bytes, list);
+            }
+            Operation::NewFaceFromHalfEdges(hedges) => {
+                bytes.push(10);
+                indices(&mut bytes, hedges);
+            }
+            Operation::NewEdgeEmbedded(a, b, layout) => {
+                bytes.push(11);
+                bytes.extend_from_slice(&a.to_le_bytes());
+                bytes.extend_from_slice(&b.to_le_bytes());
+                bytes.extend_from_slice(&layout.to_le_bytes());
+            }
+            Operation::EmbedPlanar(layout) => {
+                bytes.push(12);
+                bytes.extend_from_slice(&layout.to_le_bytes());
+            }
+            Operation::PlanarEmbedding => bytes.push(13),
+        }
+    }
+    bytes
+}
+
+impl Operation {
+    /// Applies the operation in its own transaction, failures are ignored.
+    pub fn apply<DataTypes>(&self, graph: &mut HalfEdgeGraph<DataTypes>)
+    where
+        DataTypes: Data,
+        DataTypes::Vertex: Clone,
+    {
+        let vertices: Vec<_> = graph.iter_vertices().map(|v| v.handle()).collect();
+        let faces: Vec<_> = graph.iter_faces().map(|f| f.handle()).collect();
+        let hedges: Vec<_> = graph.iter_half_edges().map(|h| h.handle()).collect();
+        let needs_vertices = !matches!(
+            self,
+            Operation::NewVertex
+                | Operation::SplitNonManifoldVertices
+                | Operation::Undo
+                | Operation::Redo
+                | Operation::PlanarEmbedding
+        );
+        if vertices.is_empty() && needs_vertices {
+            return;
+        }
+        let vertex = |index: &u16| vertices[*index as usize % vertices.len()];
+        let face = |index: &u16| faces.get(*index as usize % faces.len().max(1)).copied();
+        let indices: HashMap<_, _> = vertices.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        let layout = |layout: u16| {
+            let indices = &indices;
+            move |vertex: VertexFn<'_, DataTypes>| position(layout, indices[&vertex.handle()])
+        };
+        let polygons = |list: &[Vec<u16>]| -> Vec<Vec<_>> {
+            list.iter()
+                .map(|polygon| polygon.iter().map(vertex).collect())
+                .collect()
+        };
+
+        match self {
+            Operation::Undo => {
+                graph.undo();
+            }
+            Operation::Redo => {
+                graph.redo();
+            }
+            operation => graph.transaction(format!("{:?}", operation), |graph| match operation {
+                Operation::NewVertex => {
+                    graph.new_vertex(Default::default());
+                }
+                Operation::NewEdge(a, b) => {
+                    graph.new_edge(vertex(a), vertex(b), Default::default());
+                }
+                Operation::NewFace(indices) => {
+                    let polygon: Vec<_> = indices.iter().map(vertex).collect();
+                    graph.new_face(&polygon, Default::default());
+                }
+                Operation::ReverseFace(index) => {
+                    if let Some(face) = face(index) {
+                        let _ = graph.reverse_face(face);
+                    }
+                }
+                Operation::ReverseComponent(index) => {
+                    if let Some(face) = face(index) {
+                        let _ = graph.reverse_component(face);
+                    }
+                }
+                Operation::SplitNonManifoldVertices => {
+                    graph.split_non_manifold_vertices();
+                }
+                Operation::AddPolygons(list) => {
+                    graph.add_polygons(&polygons(list));
+                }
+                Operation::AddOrientedPolygons(list) => {
+                    graph.add_oriented_polygons(&polygons(list));
+                }
+                Operation::NewFaceFromHalfEdges(list) => {
+                    if !hedges.is_empty() {
+                        let list: Vec<_> = list
+                            .iter()
+                            .map(|index| hedges[*index as usize % hedges.len()])
+                            .collect();
+                        let _ = graph.new_face_from_half_edges(&list, Default::default());
+                    }
+                }
+                Operation::NewEdgeEmbedded(a, b, index) => {
+                    graph.new_edge_embedded(
+                        vertex(a),
+                        vertex(b),
+                        Default::default(),
+                        layout(*index),
+                    );
+                }
+                Operation::EmbedPlanar(index) => {
+                    graph.embed_planar(layout(*index));
+                }
+                Operation::PlanarEmbedding => {
+                    let _ = graph.planar_embedding();
+                }
+                Operation::Undo | Operation::Redo => unreachable!(),
+            }),
+        }
+    }
+}
+
+/// Runs the operations on a empty graph with the journal enabled,
+/// and panics if any operation breaks the invariants of the graph.
+pub fn run<DataTypes>(operations: &[Operation])
+where
+    DataTypes: Data + Default,
+    DataTypes::Vertex: Clone,
+    DataTypes::Edge: Clone,
+    DataTypes::HalfEdge: Clone,
+    DataTypes::Face: Clone,
+{
+    let mut graph = HalfEdgeGraph::<DataTypes>::default();
+    graph.enable_journal();
+
+    for (index, operation) in operations.iter().enumerate() {
+        operation.apply(&mut graph);
+        if let Err(error) = graph.validate() {
+            panic!("{} after operation {}: {:?}", error, index, operation);
+        }
+    }
+}
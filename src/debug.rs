@@ -0,0 +1,126 @@
+//! Debug output of the internal links of a graph
+//!
+//! [write_cytoscape] writes every item and its links as JSON, in the format the viewer in
+//! `tools/cytoscape` expects. The `cytoscape` example turns the JSON into a self-contained HTML file,
+//! so a broken graph from any test can be inspected in a browser:
+//!
+//! ```text
+//! cargo run --example cytoscape -- graph.json graph.html
+//! ```
+use std::fmt::Debug;
+use std::io::Write;
+
+use slotmap::Key;
+
+use crate::{Data, HalfEdgeGraph, Handle};
+
+/// Quotes and escapes `text` as a JSON string
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn json_handle<K: Key + Debug>(handle: K) -> String {
+    if handle.is_null() {
+        "null".to_string()
+    } else {
+        json_string(&format!("{:?}", handle))
+    }
+}
+
+/// Writes the graph as JSON for the cytoscape viewer, the data of each item is used as its label.
+pub fn write_cytoscape<DataTypes, W>(
+    graph: &HalfEdgeGraph<DataTypes>,
+    writer: W,
+) -> std::io::Result<()>
+where
+    DataTypes: Data,
+    W: Write,
+    DataTypes::Vertex: Debug,
+    DataTypes::Edge: Debug,
+    DataTypes::HalfEdge: Debug,
+    DataTypes::Face: Debug,
+{
+    write_cytoscape_with(graph, writer, |handle| {
+        Some(match handle {
+            Handle::Vertex(vertex) => format!("{:?}", graph[vertex]),
+            Handle::Edge(edge) => format!("{:?}", graph[edge]),
+            Handle::HalfEdge(hedge) => format!("{:?}", graph[hedge]),
+            Handle::Face(face) => format!("{:?}", graph[face]),
+        })
+    })
+}
+
+/// Writes the graph as JSON for the cytoscape viewer, with the labels returned by `label`.
+pub fn write_cytoscape_with<DataTypes, W, F>(
+    graph: &HalfEdgeGraph<DataTypes>,
+    mut writer: W,
+    label: F,
+) -> std::io::Result<()>
+where
+    DataTypes: Data,
+    W: Write,
+    F: Fn(Handle) -> Option<String>,
+{
+    let label = |handle: Handle| match label(handle) {
+        Some(label) => format!(", \"label\": {}", json_string(&label)),
+        None => String::new(),
+    };
+
+    // Writes a object with one entry per item
+    macro_rules! write_items {
+        ($name:literal, $map:ident, $last:literal, |$handle:ident, $item:ident| $fields:expr) => {
+            writeln!(writer, "  \"{}\": {{", $name)?;
+            let count = graph.$map.len();
+            for (index, ($handle, $item)) in graph.$map.iter().enumerate() {
+                let separator = if index + 1 < count { "," } else { "" };
+                writeln!(
+                    writer,
+                    "    {}: {{{}{}}}{}",
+                    json_handle($handle),
+                    $fields,
+                    label($handle.into()),
+                    separator
+                )?;
+            }
+            writeln!(writer, "  }}{}", if $last { "" } else { "," })?;
+        };
+    }
+
+    writeln!(writer, "{{")?;
+    write_items!("vertices", vertices, false, |handle, vertex| format!(
+        "\"hedge\": {}",
+        json_handle(vertex.hedge)
+    ));
+    write_items!("edges", edges, false, |handle, edge| format!(
+        "\"hedge\": {}",
+        json_handle(edge.hedge)
+    ));
+    write_items!("faces", faces, false, |handle, face| format!(
+        "\"hedge\": {}",
+        json_handle(face.hedge)
+    ));
+    write_items!("half_edges", half_edges, true, |handle, hedge| format!(
+        "\"pair\": {}, \"next\": {}, \"prev\": {}, \"vertex\": {}, \"edge\": {}, \"face\": {}",
+        json_handle(hedge.pair),
+        json_handle(hedge.next),
+        json_handle(hedge.prev),
+        json_handle(hedge.vertex),
+        json_handle(hedge.edge),
+        json_handle(hedge.face)
+    ));
+    writeln!(writer, "}}")?;
+
+    writer.flush()
+}
@@ -0,0 +1,88 @@
+//! Notification of graph mutations
+//!
+//! When enabled with [HalfEdgeGraph::enable_events] the graph queues a [GraphEvent] for every
+//! mutation, which can be collected with [HalfEdgeGraph::drain_events]. This allows caches
+//! of the graph to be updated incrementally.
+use crate::{Data, EdgeHandle, FaceHandle, HalfEdgeGraph, HalfEdgeHandle, Handle, VertexHandle};
+
+/// A mutation of a [HalfEdgeGraph]
+///
+/// The events are queued in the order the mutations happen, so a handle in a event might
+/// not be valid anymore once the event is drained (if it was removed by [HalfEdgeGraph::undo]).
+/// The same item can be reported multiple times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GraphEvent {
+    VertexAdded(VertexHandle),
+    /// A edge, and its 2 half edges was added
+    EdgeAdded(EdgeHandle),
+    FaceAdded(FaceHandle),
+
+    /// The next, prev or face link of the half edge have changed
+    HalfEdgeRelinked(HalfEdgeHandle),
+    /// The data of the item may have been modified
+    DataModified(Handle),
+
+    VertexRemoved(VertexHandle),
+    /// A edge, and its 2 half edges was removed
+    EdgeRemoved(EdgeHandle),
+    FaceRemoved(FaceHandle),
+}
+
+impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
+    /// Starts queuing events for all mutations.
+    ///
+    /// Does nothing if events already are enabled.
+    pub fn enable_events(&mut self) {
+        if self.events.is_none() {
+            self.events = Some(Vec::new());
+        }
+    }
+
+    /// Stops queuing events, and drops all queued events.
+    pub fn disable_events(&mut self) {
+        self.events = None;
+    }
+
+    pub fn is_events_enabled(&self) -> bool {
+        self.events.is_some()
+    }
+
+    /// Removes all queued events, in the order they happened.
+    pub fn drain_events(&mut self) -> impl Iterator<Item = GraphEvent> + '_ {
+        self.events.iter_mut().flat_map(|events| events.drain(..))
+    }
+
+    pub(crate) fn emit(&mut self, event: GraphEvent) {
+        if let Some(events) = &mut self.events {
+            events.push(event);
+        }
+    }
+
+    pub(crate) fn emit_added(&mut self, handle: Handle) {
+        match handle {
+            Handle::Vertex(vertex) => self.emit(GraphEvent::VertexAdded(vertex)),
+            Handle::Edge(edge) => self.emit(GraphEvent::EdgeAdded(edge)),
+            Handle::Face(face) => self.emit(GraphEvent::FaceAdded(face)),
+            // Part of EdgeAdded
+            Handle::HalfEdge(_) => {}
+        }
+    }
+
+    pub(crate) fn emit_removed(&mut self, handle: Handle) {
+        match handle {
+            Handle::Vertex(vertex) => self.emit(GraphEvent::VertexRemoved(vertex)),
+            Handle::Edge(edge) => self.emit(GraphEvent::EdgeRemoved(edge)),
+            Handle::Face(face) => self.emit(GraphEvent::FaceRemoved(face)),
+            // Part of EdgeRemoved
+            Handle::HalfEdge(_) => {}
+        }
+    }
+
+    /// Only changes to half edges are reported, the links of the other items are
+    /// implementation details
+    pub(crate) fn emit_relinked(&mut self, handle: Handle) {
+        if let Handle::HalfEdge(hedge) = handle {
+            self.emit(GraphEvent::HalfEdgeRelinked(hedge));
+        }
+    }
+}
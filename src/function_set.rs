@@ -144,6 +144,7 @@ macro_rules! fn_type {
 
         impl<'graph, DataTypes: Data> std::ops::DerefMut for $name<'graph, DataTypes> {
             fn deref_mut(&mut self) -> &mut Self::Target {
+                self.graph.record_data(self.handle);
                 &mut Self::get_mut(self).data
             }
         }
@@ -318,7 +319,7 @@ macro_rules! fn_type {
         fn $fun:ident(&mut self) -> $ret:ident;
         $($rest:tt)*
     ) => {
-        pub fn $fun(&mut self) -> $ret<DataTypes> {
+        pub fn $fun(&mut self) -> $ret<'_, DataTypes> {
             $ret::new($name_mut::new(self.graph, self.handle))
         }
         fn_type!(__impl_props_mut $name:$name_mut; $($rest)*);
@@ -423,13 +424,20 @@ impl<'graph, DataTypes: Data> EdgeFn<'graph, DataTypes> {
 
         [hedge.vertex(), pair.vertex()]
     }
+}
+
+impl<'graph, DataTypes: Data> EdgeFnMut<'graph, DataTypes> {
+    // Giving out [VertexFnMut; 2] would give out multiple mutable references to `graph`,
+    // but the data of the vertices are disjoint so that is fine
+    /// Mutable access to the data of both vertices, in the same order as [EdgeFn::vertices]
+    ///
+    /// Returns `None` if the edge is a self loop, see [Data::ALLOW_SELF_LOOPS]
+    pub fn vertices_data_mut(&mut self) -> Option<[&mut DataTypes::Vertex; 2]> {
+        let hedge = &self.graph.half_edges[Self::get(self).hedge];
+        let vertices = [hedge.vertex, self.graph.half_edges[hedge.pair].vertex];
 
-    // @todo figure out how vertices_mut can be implemented soundly
-    // The problem is that the straigt forward way gives out multiple
-    // mutable references to `graph`
-    // We would need a way to limit access to one vertex at the time
-    // pub fn vertices_mut(&self) -> [VertexFnMut<'graph, DataTypes>; 2] {
-    // }
+        self.graph.get_disjoint_vertices_mut(vertices)
+    }
 }
 
 fn_type!(
@@ -0,0 +1,403 @@
+//! Geometric queries for graphs embedded in 3d space
+//!
+//! Implement [HasPosition] for your vertex data, and [FaceFn], [EdgeFn], [VertexFn] and
+//! [HalfEdgeGraph] gains methods for normals, areas, curvature and more.
+//!
+//! ```
+//! use half_edge_graph::HalfEdgeGraph;
+//!
+//! #[derive(Default)]
+//! struct Data;
+//! impl half_edge_graph::Data for Data {
+//!     type Face = ();
+//!     type Edge = ();
+//!     type HalfEdge = ();
+//!     type Vertex = [f32; 3];
+//! }
+//!
+//! let mut graph = HalfEdgeGraph::<Data>::default();
+//! let v1 = graph.new_vertex([0., 0., 0.]);
+//! let v2 = graph.new_vertex([1., 0., 0.]);
+//! let v3 = graph.new_vertex([0., 1., 0.]);
+//! let face = graph.new_face(&[v1, v2, v3], ()).unwrap();
+//!
+//! let face = graph.face(face).unwrap();
+//! assert_eq!(face.normal(), [0., 0., 1.]);
+//! assert_eq!(face.area(), 0.5);
+//! ```
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use itertools::Itertools;
+
+use crate::{Data, EdgeFn, FaceFn, HalfEdgeFn, HalfEdgeGraph, VertexFn};
+
+/// Floating point type used for positions, implemented for [f32] and [f64]
+pub trait Scalar:
+    Copy
+    + Debug
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+
+    fn zero() -> Self {
+        Self::from_f64(0.)
+    }
+}
+
+macro_rules! impl_scalar {
+    ($type:ident) => {
+        impl Scalar for $type {
+            fn from_f64(value: f64) -> Self {
+                value as $type
+            }
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+            fn sqrt(self) -> Self {
+                $type::sqrt(self)
+            }
+            fn abs(self) -> Self {
+                $type::abs(self)
+            }
+            fn atan2(self, other: Self) -> Self {
+                $type::atan2(self, other)
+            }
+        }
+    };
+}
+
+impl_scalar!(f32);
+impl_scalar!(f64);
+
+/// Implemented by vertex data that have a position
+pub trait HasPosition {
+    type Scalar: Scalar;
+
+    fn position(&self) -> [Self::Scalar; 3];
+}
+
+impl<S: Scalar> HasPosition for [S; 3] {
+    type Scalar = S;
+
+    fn position(&self) -> [S; 3] {
+        *self
+    }
+}
+
+/// How the normals of the faces around a vertex are weighted, see [VertexFn::normal]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalWeighting {
+    /// Every face contributes equally
+    Uniform,
+    /// Faces are weighted by their area
+    Area,
+    /// Faces are weighted by the angle of the corner at the vertex
+    Angle,
+}
+
+type Vec3<S> = [S; 3];
+type ScalarOf<DataTypes> = <<DataTypes as Data>::Vertex as HasPosition>::Scalar;
+
+fn add<S: Scalar>(a: Vec3<S>, b: Vec3<S>) -> Vec3<S> {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub<S: Scalar>(a: Vec3<S>, b: Vec3<S>) -> Vec3<S> {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale<S: Scalar>(a: Vec3<S>, s: S) -> Vec3<S> {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+fn dot<S: Scalar>(a: Vec3<S>, b: Vec3<S>) -> S {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross<S: Scalar>(a: Vec3<S>, b: Vec3<S>) -> Vec3<S> {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn length<S: Scalar>(a: Vec3<S>) -> S {
+    dot(a, a).sqrt()
+}
+
+fn normalize<S: Scalar>(a: Vec3<S>) -> Vec3<S> {
+    let len = length(a);
+    if len > S::zero() {
+        scale(a, S::from_f64(1.) / len)
+    } else {
+        a
+    }
+}
+
+fn angle_between<S: Scalar>(a: Vec3<S>, b: Vec3<S>) -> S {
+    length(cross(a, b)).atan2(dot(a, b))
+}
+
+fn position<DataTypes: Data>(vertex: VertexFn<'_, DataTypes>) -> Vec3<ScalarOf<DataTypes>>
+where
+    DataTypes::Vertex: HasPosition,
+{
+    vertex.position()
+}
+
+/// The angle at the corner where `hedge` enters its vertex
+fn corner_angle<DataTypes: Data>(hedge: &HalfEdgeFn<'_, DataTypes>) -> ScalarOf<DataTypes>
+where
+    DataTypes::Vertex: HasPosition,
+{
+    let p = position(hedge.vertex());
+    let prev = position(hedge.pair().vertex());
+    let next = position(hedge.next().vertex());
+
+    angle_between(sub(prev, p), sub(next, p))
+}
+
+impl<'graph, DataTypes: Data> FaceFn<'graph, DataTypes>
+where
+    DataTypes::Vertex: HasPosition,
+{
+    /// Normal vector scaled by twice the area, using Newell's method
+    fn area_vector(&self) -> Vec3<ScalarOf<DataTypes>> {
+        let positions: Vec<_> = self.vertices().map(position).collect();
+        positions
+            .iter()
+            .circular_tuple_windows()
+            .fold([ScalarOf::<DataTypes>::zero(); 3], |sum, (p1, p2)| {
+                add(sum, cross(*p1, *p2))
+            })
+    }
+
+    /// Unit normal of the face, for non planar faces this is the average normal.
+    pub fn normal(&self) -> Vec3<ScalarOf<DataTypes>> {
+        normalize(self.area_vector())
+    }
+
+    pub fn area(&self) -> ScalarOf<DataTypes> {
+        length(self.area_vector()) * ScalarOf::<DataTypes>::from_f64(0.5)
+    }
+
+    /// Area weighted center of the face.
+    ///
+    /// Falls back to the average of the vertices for degenerated faces.
+    pub fn centroid(&self) -> Vec3<ScalarOf<DataTypes>> {
+        let normal = self.normal();
+        let positions: Vec<_> = self.vertices().map(position).collect();
+
+        let mut center = [ScalarOf::<DataTypes>::zero(); 3];
+        let mut total: ScalarOf<DataTypes> = ScalarOf::<DataTypes>::zero();
+        for i in 1..positions.len().saturating_sub(1) {
+            let (p0, p1, p2) = (positions[0], positions[i], positions[i + 1]);
+            let area = dot(cross(sub(p1, p0), sub(p2, p0)), normal);
+            let triangle_center = scale(
+                add(add(p0, p1), p2),
+                ScalarOf::<DataTypes>::from_f64(1. / 3.),
+            );
+
+            center = add(center, scale(triangle_center, area));
+            total = total + area;
+        }
+
+        if total.abs() > ScalarOf::<DataTypes>::zero() {
+            scale(center, ScalarOf::<DataTypes>::from_f64(1.) / total)
+        } else {
+            let sum = positions
+                .iter()
+                .fold([ScalarOf::<DataTypes>::zero(); 3], |sum, p| add(sum, *p));
+            scale(
+                sum,
+                ScalarOf::<DataTypes>::from_f64(1. / positions.len() as f64),
+            )
+        }
+    }
+
+    /// Checks that all vertices are within `eps` of the plane of the face
+    pub fn is_planar(&self, eps: ScalarOf<DataTypes>) -> bool {
+        let normal = self.normal();
+        let center = self.centroid();
+
+        self.vertices()
+            .all(|v| dot(sub(position(v), center), normal).abs() <= eps)
+    }
+}
+
+impl<'graph, DataTypes: Data> EdgeFn<'graph, DataTypes>
+where
+    DataTypes::Vertex: HasPosition,
+{
+    pub fn length(&self) -> ScalarOf<DataTypes> {
+        let [v1, v2] = self.vertices();
+        length(sub(position(v1), position(v2)))
+    }
+
+    /// Signed angle between the normals of the two faces of the edge.
+    ///
+    /// The angle is positive if the faces are convex, negative if concave and zero if they are
+    /// coplanar. Returns `None` if the edge doesn't have two faces.
+    pub fn dihedral_angle(&self) -> Option<ScalarOf<DataTypes>> {
+        let hedge = self.hedge();
+        let n1 = hedge.face()?.normal();
+        let n2 = hedge.pair().face()?.normal();
+
+        let direction = sub(position(hedge.vertex()), position(hedge.pair().vertex()));
+        let angle = angle_between(n1, n2);
+
+        if dot(cross(n1, n2), direction) < ScalarOf::<DataTypes>::zero() {
+            Some(-angle)
+        } else {
+            Some(angle)
+        }
+    }
+}
+
+impl<'graph, DataTypes: Data> VertexFn<'graph, DataTypes>
+where
+    DataTypes::Vertex: HasPosition,
+{
+    /// Normal of the vertex, calculated from the normals of the surrounding faces
+    pub fn normal(&self, weighting: NormalWeighting) -> Vec3<ScalarOf<DataTypes>> {
+        let mut result = [ScalarOf::<DataTypes>::zero(); 3];
+
+        for hedge in self.in_half_edges() {
+            let face = match hedge.face() {
+                Some(face) => face,
+                None => continue,
+            };
+            let weight = match weighting {
+                NormalWeighting::Uniform => ScalarOf::<DataTypes>::from_f64(1.),
+                NormalWeighting::Area => face.area(),
+                NormalWeighting::Angle => corner_angle(&hedge),
+            };
+            result = add(result, scale(face.normal(), weight));
+        }
+
+        normalize(result)
+    }
+
+    /// Returns true if the vertex is on the boundary of the surface (or have no faces)
+    fn is_boundary(&self) -> bool {
+        let mut hedges = self.in_half_edges().peekable();
+        hedges.peek().is_none() || hedges.any(|hedge| hedge.face().is_none())
+    }
+
+    /// The area associated with the vertex, a third of the surrounding faces area
+    fn vertex_area(&self) -> ScalarOf<DataTypes> {
+        self.faces().fold(
+            ScalarOf::<DataTypes>::zero(),
+            |sum: ScalarOf<DataTypes>, face| sum + face.area(),
+        ) * ScalarOf::<DataTypes>::from_f64(1. / 3.)
+    }
+
+    /// The integrated gaussian curvature around the vertex,
+    /// `2π` minus the sum of the angles around the vertex (`π` for boundary vertices).
+    pub fn angle_defect(&self) -> ScalarOf<DataTypes> {
+        let full = if self.is_boundary() {
+            std::f64::consts::PI
+        } else {
+            2. * std::f64::consts::PI
+        };
+
+        self.in_half_edges()
+            .filter(|hedge| hedge.face().is_some())
+            .fold(ScalarOf::<DataTypes>::from_f64(full), |sum, hedge| {
+                sum - corner_angle(&hedge)
+            })
+    }
+
+    /// Gaussian curvature at the vertex, the [angle defect](VertexFn::angle_defect) divided by
+    /// the area around the vertex.
+    ///
+    /// Returns `None` if the surrounding faces have no area, like for a vertex without faces.
+    pub fn gaussian_curvature(&self) -> Option<ScalarOf<DataTypes>> {
+        Some(self.angle_defect() / self.nonzero_vertex_area()?)
+    }
+
+    /// Mean curvature at the vertex, calculated from the dihedral angles of the surrounding edges.
+    ///
+    /// Returns `None` if the surrounding faces have no area, like for a vertex without faces.
+    pub fn mean_curvature(&self) -> Option<ScalarOf<DataTypes>> {
+        let area = self.nonzero_vertex_area()?;
+        let sum = self
+            .edges()
+            .filter_map(|edge| Some(edge.length() * edge.dihedral_angle()?))
+            .fold(
+                ScalarOf::<DataTypes>::zero(),
+                |sum: ScalarOf<DataTypes>, v| sum + v,
+            );
+
+        Some(sum * ScalarOf::<DataTypes>::from_f64(0.25) / area)
+    }
+
+    /// The [vertex area](VertexFn::vertex_area), or `None` if it is zero (or NaN)
+    fn nonzero_vertex_area(&self) -> Option<ScalarOf<DataTypes>> {
+        let area = self.vertex_area();
+        if area > ScalarOf::<DataTypes>::zero() {
+            Some(area)
+        } else {
+            None
+        }
+    }
+}
+
+impl<DataTypes: Data> HalfEdgeGraph<DataTypes>
+where
+    DataTypes::Vertex: HasPosition,
+{
+    /// Returns the min and max corner of the bounding box, or `None` if the graph is empty
+    #[allow(clippy::type_complexity)]
+    pub fn bounding_box(&self) -> Option<(Vec3<ScalarOf<DataTypes>>, Vec3<ScalarOf<DataTypes>>)> {
+        let mut positions = self.vertices.values().map(|v| v.data.position());
+        let first = positions.next()?;
+
+        Some(positions.fold((first, first), |(mut min, mut max), p| {
+            for i in 0..3 {
+                if p[i] < min[i] {
+                    min[i] = p[i];
+                }
+                if p[i] > max[i] {
+                    max[i] = p[i];
+                }
+            }
+            (min, max)
+        }))
+    }
+
+    /// The total area of all faces
+    pub fn surface_area(&self) -> ScalarOf<DataTypes> {
+        self.iter_faces().fold(
+            ScalarOf::<DataTypes>::zero(),
+            |sum: ScalarOf<DataTypes>, face| sum + face.area(),
+        )
+    }
+
+    /// The signed volume enclosed by the faces.
+    ///
+    /// Only meaningfull for closed surfaces, the volume is positive if the faces are
+    /// counter clockwise when seen from the outside.
+    pub fn volume(&self) -> ScalarOf<DataTypes> {
+        let mut volume: ScalarOf<DataTypes> = ScalarOf::<DataTypes>::zero();
+        for face in self.iter_faces() {
+            let positions: Vec<_> = face.vertices().map(position).collect();
+            for i in 1..positions.len().saturating_sub(1) {
+                let (p0, p1, p2) = (positions[0], positions[i], positions[i + 1]);
+                volume = volume + dot(p0, cross(p1, p2));
+            }
+        }
+        volume * ScalarOf::<DataTypes>::from_f64(1. / 6.)
+    }
+}
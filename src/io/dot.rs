@@ -0,0 +1,110 @@
+//! Graphviz DOT export, for debugging and documentation
+//!
+//! ```
+//! use half_edge_graph::{io::ExportOptions, HalfEdgeGraph};
+//!
+//! let mut graph = HalfEdgeGraph::<()>::default();
+//! let v1 = graph.new_vertex(());
+//! let v2 = graph.new_vertex(());
+//! let v3 = graph.new_vertex(());
+//! graph.new_face(&[v1, v2, v3], ()).unwrap();
+//!
+//! let mut dot = Vec::new();
+//! let options = ExportOptions::<()>::default()
+//!     .vertex_label(|vertex| format!("{} edges", vertex.edges().count()))
+//!     .half_edges(true);
+//! graph.to_dot(&mut dot, &options).unwrap();
+//! assert!(String::from_utf8(dot).unwrap().starts_with("digraph {"));
+//! ```
+use std::collections::HashSet;
+use std::io::Write;
+
+use slotmap::SecondaryMap;
+
+use super::ExportOptions;
+use crate::{Data, FaceFn, HalfEdgeGraph};
+
+/// Escapes `text` for use in a quoted DOT string
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
+    /// Writes the vertices and edges of the graph in the Graphviz DOT format.
+    ///
+    /// With [ExportOptions::face_clusters] each face is drawn as a cluster around its vertices, since
+    /// Graphviz only allows a node to be part of one cluster, each vertex is placed in the first face
+    /// it is part of. With [ExportOptions::half_edges] the half edges are drawn as arcs instead of the edges.
+    pub fn to_dot<W: Write>(
+        &self,
+        mut writer: W,
+        options: &ExportOptions<'_, DataTypes>,
+    ) -> std::io::Result<()> {
+        let mut vertices = SecondaryMap::new();
+        for (index, handle) in self.vertices.keys().enumerate() {
+            vertices.insert(handle, index);
+        }
+
+        writeln!(writer, "digraph {{")?;
+
+        let mut placed = HashSet::new();
+        if options.face_clusters {
+            for (index, face) in self.faces.keys().enumerate() {
+                let label = options.face(FaceFn::new(self, face), index);
+                writeln!(writer, "  subgraph cluster_f{} {{", index)?;
+                writeln!(writer, "    label=\"{}\";", escape(&label))?;
+                for vertex in FaceFn::new(self, face).vertices() {
+                    if placed.insert(vertex.handle()) {
+                        let index = vertices[vertex.handle()];
+                        let label = options.vertex(vertex, index);
+                        writeln!(writer, "    v{} [label=\"{}\"];", index, escape(&label))?;
+                    }
+                }
+                writeln!(writer, "  }}")?;
+            }
+        }
+
+        for vertex in self.iter_vertices() {
+            if !placed.contains(&vertex.handle()) {
+                let index = vertices[vertex.handle()];
+                let label = options.vertex(vertex, index);
+                writeln!(writer, "  v{} [label=\"{}\"];", index, escape(&label))?;
+            }
+        }
+
+        if options.half_edges {
+            let mut hedges = SecondaryMap::new();
+            for (index, handle) in self.half_edges.keys().enumerate() {
+                hedges.insert(handle, index);
+            }
+
+            for hedge in self.iter_half_edges() {
+                writeln!(
+                    writer,
+                    "  v{} -> v{} [label=\"h{}\\nnext h{}\\nprev h{}\"];",
+                    vertices[hedge.pair().vertex().handle()],
+                    vertices[hedge.vertex().handle()],
+                    hedges[hedge.handle()],
+                    hedges[hedge.next().handle()],
+                    hedges[hedge.prev().handle()],
+                )?;
+            }
+        } else {
+            for (index, edge) in self.iter_edges().enumerate() {
+                let [v1, v2] = edge.vertices();
+                writeln!(
+                    writer,
+                    "  v{} -> v{} [dir=none, label=\"{}\"];",
+                    vertices[v1.handle()],
+                    vertices[v2.handle()],
+                    escape(&options.edge(edge, index)),
+                )?;
+            }
+        }
+
+        writeln!(writer, "}}")?;
+        writer.flush()
+    }
+}
@@ -0,0 +1,413 @@
+//! GraphML export and import of the connectivity of a graph
+//!
+//! Only vertices and edges are part of the file, so faces are lost when writing.
+//! The labels are written as a `label` attribute.
+//!
+//! The reader supports the subset of XML that GraphML files use: tags, comments, CDATA sections,
+//! the predefined entities and character references. Entities declared in a DOCTYPE are not
+//! supported and fail with [ReadError::Parse]. The `default` of a key is used for nodes and
+//! edges that don't have a `data` element for it.
+//!
+//! ```
+//! use half_edge_graph::{io::ExportOptions, HalfEdgeGraph};
+//!
+//! let mut graph = HalfEdgeGraph::<()>::default();
+//! let v1 = graph.new_vertex(());
+//! let v2 = graph.new_vertex(());
+//! graph.new_edge(v1, v2, ()).unwrap();
+//!
+//! let mut file = Vec::new();
+//! graph.to_graphml(&mut file, &ExportOptions::default()).unwrap();
+//!
+//! let read = HalfEdgeGraph::<()>::from_graphml(&file[..], |_| (), |_| ()).unwrap();
+//! assert!(read.is_isomorphic(&graph));
+//! ```
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use slotmap::SecondaryMap;
+
+use super::{escape_xml, ExportOptions, ReadError};
+use crate::{Data, HalfEdgeGraph};
+
+/// The `data` of a node or edge, by the `attr.name` of the key (or the id of the key if it doesn't have a name)
+pub type Attributes = HashMap<String, String>;
+
+enum Token {
+    Open {
+        name: String,
+        attributes: HashMap<String, String>,
+        /// Self closing tag, `<name/>`
+        closed: bool,
+    },
+    Close(String),
+    Text(String),
+}
+
+/// Replaces the predefined entities and the character references, like `&#38;` or `&#x3C;`
+fn unescape_xml(text: &str) -> Result<String, ReadError> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        let end = rest
+            .find(';')
+            .ok_or_else(|| ReadError::parse("unterminated entity"))?;
+        let entity = &rest[..end];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            "amp" => '&',
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|decimal| decimal.parse()))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32)
+                .ok_or_else(|| ReadError::parse(format!("unsupported entity &{};", entity)))?,
+        };
+        unescaped.push(c);
+        rest = &rest[end + 1..];
+    }
+
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
+
+/// Splits the xml into tags and text, comments and declarations are skipped.
+/// The content of a CDATA section is returned as text, without unescaping it.
+fn tokenize(xml: &str) -> Result<Vec<Token>, ReadError> {
+    let mut tokens = Vec::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        let text = &rest[..start];
+        if !text.trim().is_empty() {
+            tokens.push(Token::Text(unescape_xml(text)?));
+        }
+        rest = &rest[start..];
+
+        let skip_to = |rest: &str, end: &str| {
+            rest.find(end)
+                .map(|index| index + end.len())
+                .ok_or_else(|| ReadError::parse(format!("missing {}", end)))
+        };
+        if rest.starts_with("<!--") {
+            rest = &rest[skip_to(rest, "-->")?..];
+            continue;
+        }
+        if rest.starts_with("<?") {
+            rest = &rest[skip_to(rest, "?>")?..];
+            continue;
+        }
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = skip_to(cdata, "]]>")?;
+            tokens.push(Token::Text(cdata[..end - 3].to_string()));
+            rest = &cdata[end..];
+            continue;
+        }
+        if rest.starts_with("<!") {
+            let end = skip_to(rest, ">")?;
+            // An internal DTD subset can declare entities, and contain '>' itself
+            if rest[..end].contains('[') {
+                return Err(ReadError::parse("DOCTYPE declarations are not supported"));
+            }
+            rest = &rest[end..];
+            continue;
+        }
+
+        // Find the end of the tag, ignoring '>' in quoted values
+        let mut quote = None;
+        let end = rest
+            .char_indices()
+            .find(|(_, c)| match quote {
+                Some(q) if *c == q => {
+                    quote = None;
+                    false
+                }
+                Some(_) => false,
+                None if *c == '"' || *c == '\'' => {
+                    quote = Some(*c);
+                    false
+                }
+                None => *c == '>',
+            })
+            .map(|(index, _)| index)
+            .ok_or_else(|| ReadError::parse("unterminated tag"))?;
+
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim().to_string()));
+            continue;
+        }
+
+        let closed = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let name = tag[..name_end].to_string();
+
+        let mut attributes = HashMap::new();
+        let mut attribute_text = &tag[name_end..];
+        while let Some(equals) = attribute_text.find('=') {
+            let key = attribute_text[..equals].trim().to_string();
+            let value = attribute_text[equals + 1..].trim_start();
+            let quote = value
+                .chars()
+                .next()
+                .filter(|c| *c == '"' || *c == '\'')
+                .ok_or_else(|| ReadError::parse(format!("unquoted attribute {}", key)))?;
+            let value_end = value[1..]
+                .find(quote)
+                .ok_or_else(|| ReadError::parse(format!("unterminated attribute {}", key)))?;
+            attributes.insert(key, unescape_xml(&value[1..value_end + 1])?);
+            attribute_text = &value[value_end + 2..];
+        }
+
+        tokens.push(Token::Open {
+            name,
+            attributes,
+            closed,
+        });
+    }
+
+    Ok(tokens)
+}
+
+impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
+    /// Writes the vertices and edges of the graph as GraphML, the labels of the vertices and edges
+    /// are written as the `label` attribute.
+    pub fn to_graphml<W: Write>(
+        &self,
+        mut writer: W,
+        options: &ExportOptions<'_, DataTypes>,
+    ) -> std::io::Result<()> {
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            writer,
+            r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+        )?;
+        writeln!(
+            writer,
+            r#"  <key id="label" for="all" attr.name="label" attr.type="string"/>"#
+        )?;
+        writeln!(writer, r#"  <graph id="G" edgedefault="undirected">"#)?;
+
+        let mut vertices = SecondaryMap::new();
+        for (index, vertex) in self.iter_vertices().enumerate() {
+            vertices.insert(vertex.handle(), index);
+            writeln!(
+                writer,
+                r#"    <node id="v{}"><data key="label">{}</data></node>"#,
+                index,
+                escape_xml(&options.vertex(vertex, index))
+            )?;
+        }
+
+        for (index, edge) in self.iter_edges().enumerate() {
+            let [v1, v2] = edge.vertices();
+            writeln!(
+                writer,
+                r#"    <edge id="e{}" source="v{}" target="v{}"><data key="label">{}</data></edge>"#,
+                index,
+                vertices[v1.handle()],
+                vertices[v2.handle()],
+                escape_xml(&options.edge(edge, index))
+            )?;
+        }
+
+        writeln!(writer, "  </graph>")?;
+        writeln!(writer, "</graphml>")?;
+        writer.flush()
+    }
+
+    /// Reads a graph without faces from GraphML.
+    ///
+    /// The data of the vertices and edges is created by `vertex` and `edge`, from the `data` elements
+    /// of each node and edge. Fails if a edge can't be added, for example if it is a parallel edge
+    /// and [Data::ALLOW_PARALLEL_EDGES] is false.
+    pub fn from_graphml<R, V, E>(
+        mut reader: R,
+        mut vertex: V,
+        mut edge: E,
+    ) -> Result<Self, ReadError>
+    where
+        DataTypes: Default,
+        R: Read,
+        V: FnMut(&Attributes) -> DataTypes::Vertex,
+        E: FnMut(&Attributes) -> DataTypes::Edge,
+    {
+        let mut xml = String::new();
+        reader.read_to_string(&mut xml)?;
+
+        let mut graph = HalfEdgeGraph::default();
+        let mut keys = HashMap::new();
+        // The default values of the keys, by the element they are for
+        let mut defaults: HashMap<String, Attributes> = HashMap::new();
+        let mut nodes = HashMap::new();
+        let mut edges = Vec::new();
+
+        // The node or edge being read, with its attributes
+        let mut current: Option<(String, HashMap<String, String>)> = None;
+        let mut attributes = Attributes::new();
+        // The attribute that text is added to, from a `data` or the `default` of a key
+        let mut data_key: Option<String> = None;
+        // The key being read, with the element it is for
+        let mut key: Option<(String, String)> = None;
+
+        for token in tokenize(&xml)? {
+            match token {
+                Token::Open {
+                    name,
+                    attributes: tag,
+                    closed,
+                } => match name.as_str() {
+                    "key" => {
+                        let id = tag
+                            .get("id")
+                            .ok_or_else(|| ReadError::parse("key without id"))?;
+                        let name = tag.get("attr.name").unwrap_or(id);
+                        keys.insert(id.clone(), name.clone());
+                        if !closed {
+                            let domain = tag.get("for").map_or("all", String::as_str);
+                            key = Some((name.clone(), domain.to_string()));
+                        }
+                    }
+                    "default" if !closed => {
+                        if let Some((name, domain)) = &key {
+                            let defaults = defaults.entry(domain.clone()).or_default();
+                            defaults.insert(name.clone(), String::new());
+                            data_key = Some(name.clone());
+                        }
+                    }
+                    "node" | "edge" => {
+                        attributes.clear();
+                        for domain in [name.as_str(), "all"].iter() {
+                            if let Some(defaults) = defaults.get(*domain) {
+                                attributes.extend(defaults.clone());
+                            }
+                        }
+                        current = Some((name.clone(), tag));
+                        if closed {
+                            finish(
+                                &mut graph,
+                                &mut nodes,
+                                &mut edges,
+                                &mut current,
+                                &attributes,
+                                &mut vertex,
+                            )?;
+                        }
+                    }
+                    "data" if !closed && current.is_some() => {
+                        if let Some(id) = tag.get("key") {
+                            let name = keys.get(id).unwrap_or(id).clone();
+                            attributes.insert(name.clone(), String::new());
+                            data_key = Some(name);
+                        }
+                    }
+                    _ => {}
+                },
+                // Text can be split by comments and CDATA sections, so it is added up
+                Token::Text(text) => {
+                    if let Some(name) = &data_key {
+                        if let Some(value) = data_value(&key, &mut defaults, &mut attributes, name)
+                        {
+                            value.push_str(&text);
+                        }
+                    }
+                }
+                Token::Close(name) => match name.as_str() {
+                    "data" | "default" => {
+                        if let Some(name) = data_key.take() {
+                            if let Some(value) =
+                                data_value(&key, &mut defaults, &mut attributes, &name)
+                            {
+                                *value = value.trim().to_string();
+                            }
+                        }
+                    }
+                    "key" => key = None,
+                    "node" | "edge" => {
+                        finish(
+                            &mut graph,
+                            &mut nodes,
+                            &mut edges,
+                            &mut current,
+                            &attributes,
+                            &mut vertex,
+                        )?;
+                    }
+                    _ => {}
+                },
+            }
+        }
+
+        // Edges can reference nodes defined after them
+        for (source, target, attributes) in edges {
+            let find = |id: &String| {
+                nodes
+                    .get(id)
+                    .copied()
+                    .ok_or_else(|| ReadError::parse(format!("unknown node {}", id)))
+            };
+            let (v1, v2) = (find(&source)?, find(&target)?);
+            graph.new_edge(v1, v2, edge(&attributes)).ok_or_else(|| {
+                ReadError::parse(format!("edge {} - {} can't be added", source, target))
+            })?;
+        }
+
+        Ok(graph)
+    }
+}
+
+type PendingEdge = (String, String, Attributes);
+
+/// The value of the attribute `name` that is being read, in the defaults if inside of a `key`
+fn data_value<'a>(
+    key: &Option<(String, String)>,
+    defaults: &'a mut HashMap<String, Attributes>,
+    attributes: &'a mut Attributes,
+    name: &str,
+) -> Option<&'a mut String> {
+    match key {
+        Some((_, domain)) => defaults.get_mut(domain)?.get_mut(name),
+        None => attributes.get_mut(name),
+    }
+}
+
+/// Adds the node, or queues the edge, that has been read
+fn finish<DataTypes: Data, V>(
+    graph: &mut HalfEdgeGraph<DataTypes>,
+    nodes: &mut HashMap<String, crate::VertexHandle>,
+    edges: &mut Vec<PendingEdge>,
+    current: &mut Option<(String, HashMap<String, String>)>,
+    attributes: &Attributes,
+    vertex: &mut V,
+) -> Result<(), ReadError>
+where
+    V: FnMut(&Attributes) -> DataTypes::Vertex,
+{
+    let (name, tag) = match current.take() {
+        Some(current) => current,
+        None => return Ok(()),
+    };
+
+    let get = |key: &str| {
+        tag.get(key)
+            .cloned()
+            .ok_or_else(|| ReadError::parse(format!("{} without {}", name, key)))
+    };
+    if name == "node" {
+        let handle = graph.new_vertex(vertex(attributes));
+        nodes.insert(get("id")?, handle);
+    } else {
+        edges.push((get("source")?, get("target")?, attributes.clone()));
+    }
+    Ok(())
+}
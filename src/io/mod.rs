@@ -0,0 +1,193 @@
+//! Reading and writing graphs in common file formats
+//!
+//!  - [ply] Stanford PLY, ascii and binary
+//!  - [off] Object File Format, indexed polygons
+//!  - [stl] STL, ascii and binary triangle soups
+//!  - [dot] Graphviz DOT, export only
+//!  - [graphml] GraphML, the connectivity without faces
+use crate::{Data, EdgeFn, FaceError, FaceFn, VertexFn};
+
+pub mod dot;
+pub mod graphml;
+pub mod off;
+pub mod ply;
+pub mod stl;
+
+type VertexLabel<'a, DataTypes> = Box<dyn for<'g> Fn(VertexFn<'g, DataTypes>) -> String + 'a>;
+type EdgeLabel<'a, DataTypes> = Box<dyn for<'g> Fn(EdgeFn<'g, DataTypes>) -> String + 'a>;
+type FaceLabel<'a, DataTypes> = Box<dyn for<'g> Fn(FaceFn<'g, DataTypes>) -> String + 'a>;
+
+/// Options for [HalfEdgeGraph::to_dot](crate::HalfEdgeGraph::to_dot) and
+/// [HalfEdgeGraph::to_graphml](crate::HalfEdgeGraph::to_graphml)
+///
+/// By default the items are labeled with their index, in iteration order.
+pub struct ExportOptions<'a, DataTypes: Data> {
+    vertex_label: Option<VertexLabel<'a, DataTypes>>,
+    edge_label: Option<EdgeLabel<'a, DataTypes>>,
+    face_label: Option<FaceLabel<'a, DataTypes>>,
+    face_clusters: bool,
+    half_edges: bool,
+}
+
+impl<'a, DataTypes: Data> Default for ExportOptions<'a, DataTypes> {
+    fn default() -> Self {
+        Self {
+            vertex_label: None,
+            edge_label: None,
+            face_label: None,
+            face_clusters: false,
+            half_edges: false,
+        }
+    }
+}
+
+impl<'a, DataTypes: Data> ExportOptions<'a, DataTypes> {
+    pub fn vertex_label(
+        mut self,
+        label: impl for<'g> Fn(VertexFn<'g, DataTypes>) -> String + 'a,
+    ) -> Self {
+        self.vertex_label = Some(Box::new(label));
+        self
+    }
+
+    pub fn edge_label(
+        mut self,
+        label: impl for<'g> Fn(EdgeFn<'g, DataTypes>) -> String + 'a,
+    ) -> Self {
+        self.edge_label = Some(Box::new(label));
+        self
+    }
+
+    pub fn face_label(
+        mut self,
+        label: impl for<'g> Fn(FaceFn<'g, DataTypes>) -> String + 'a,
+    ) -> Self {
+        self.face_label = Some(Box::new(label));
+        self
+    }
+
+    /// Render each face as a cluster of its vertices (DOT only)
+    pub fn face_clusters(mut self, enabled: bool) -> Self {
+        self.face_clusters = enabled;
+        self
+    }
+
+    /// Render the half edges as directed arcs, labeled with their next & prev half edges (DOT only)
+    pub fn half_edges(mut self, enabled: bool) -> Self {
+        self.half_edges = enabled;
+        self
+    }
+}
+
+impl<'a, DataTypes: Data> ExportOptions<'a, DataTypes> {
+    fn vertex(&self, vertex: VertexFn<'_, DataTypes>, index: usize) -> String {
+        match &self.vertex_label {
+            Some(label) => label(vertex),
+            None => index.to_string(),
+        }
+    }
+
+    fn edge(&self, edge: EdgeFn<'_, DataTypes>, index: usize) -> String {
+        match &self.edge_label {
+            Some(label) => label(edge),
+            None => index.to_string(),
+        }
+    }
+
+    fn face(&self, face: FaceFn<'_, DataTypes>, index: usize) -> String {
+        match &self.face_label {
+            Some(label) => label(face),
+            None => index.to_string(),
+        }
+    }
+}
+
+/// Escapes `text` for use in a xml attribute or text
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Vertex data that is only a position, used by the formats that doesn't have any other properties
+///
+/// This is separate from `geometry::HasPosition`, since the io module doesn't need the `geometry`
+/// feature, and reading needs to create the vertex data from a position, not only read it.
+pub trait VertexPosition {
+    fn from_position(position: [f64; 3]) -> Self;
+    fn position(&self) -> [f64; 3];
+}
+
+impl VertexPosition for [f32; 3] {
+    fn from_position(position: [f64; 3]) -> Self {
+        [position[0] as f32, position[1] as f32, position[2] as f32]
+    }
+
+    fn position(&self) -> [f64; 3] {
+        [self[0] as f64, self[1] as f64, self[2] as f64]
+    }
+}
+
+impl VertexPosition for [f64; 3] {
+    fn from_position(position: [f64; 3]) -> Self {
+        position
+    }
+
+    fn position(&self) -> [f64; 3] {
+        *self
+    }
+}
+
+/// Reasons why reading a graph can fail
+#[derive(Debug)]
+pub enum ReadError {
+    Io(std::io::Error),
+    /// The file isn't valid, with a description of the problem
+    Parse(String),
+    /// The face with the given index (in the file) couldn't be added to the graph
+    Face {
+        index: usize,
+        error: FaceError,
+    },
+}
+
+impl ReadError {
+    pub(crate) fn parse(message: impl Into<String>) -> Self {
+        ReadError::Parse(message.into())
+    }
+}
+
+impl std::fmt::Display for ReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadError::Io(error) => write!(f, "{}", error),
+            ReadError::Parse(message) => write!(f, "invalid file: {}", message),
+            ReadError::Face { index, error } => write!(f, "face {}: {}", index, error),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(error) => Some(error),
+            ReadError::Parse(_) => None,
+            ReadError::Face { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<std::io::Error> for ReadError {
+    fn from(error: std::io::Error) -> Self {
+        ReadError::Io(error)
+    }
+}
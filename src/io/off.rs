@@ -0,0 +1,165 @@
+//! Object File Format (OFF)
+//!
+//! A list of vertex positions, followed by polygons indexing into them. Colors and other values after
+//! the positions or indices are ignored when reading.
+//!
+//! ```
+//! use half_edge_graph::{io::off, HalfEdgeGraph};
+//!
+//! #[derive(Default)]
+//! struct Data;
+//! impl half_edge_graph::Data for Data {
+//!     type Face = ();
+//!     type Edge = ();
+//!     type HalfEdge = ();
+//!     type Vertex = [f32; 3];
+//! }
+//!
+//! let file = "OFF
+//! 4 2 0
+//! 0 0 0
+//! 1 0 0
+//! 1 1 0
+//! 0 1 0
+//! 3 0 1 2
+//! 3 0 2 3
+//! ";
+//! let graph: HalfEdgeGraph<Data> = off::read(file.as_bytes()).unwrap();
+//! assert_eq!(graph.iter_edges().count(), 5);
+//! ```
+use std::collections::VecDeque;
+use std::io::{BufRead, Write};
+
+use slotmap::SecondaryMap;
+
+use super::{ReadError, VertexPosition};
+use crate::{Data, HalfEdgeGraph};
+
+struct Tokens<R> {
+    reader: R,
+    /// The tokens of the current line
+    line: VecDeque<String>,
+}
+
+impl<R: BufRead> Tokens<R> {
+    /// Moves to the next line that isn't empty or a comment
+    fn next_line(&mut self) -> Result<(), ReadError> {
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(ReadError::parse("unexpected end of file"));
+            }
+            let line = line.split('#').next().unwrap();
+            self.line = line.split_whitespace().map(str::to_string).collect();
+            if !self.line.is_empty() {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse<T: std::str::FromStr>(&mut self, what: &str) -> Result<T, ReadError> {
+        let token = self
+            .line
+            .pop_front()
+            .ok_or_else(|| ReadError::parse(format!("missing {}", what)))?;
+        token
+            .parse()
+            .map_err(|_| ReadError::parse(format!("invalid {} '{}'", what, token)))
+    }
+}
+
+/// Reads a graph from a OFF file.
+///
+/// Faces are added with [HalfEdgeGraph::add_polygons], so edges shared by more than 2 faces
+/// are glued instead of failing.
+pub fn read<DataTypes, R>(reader: R) -> Result<HalfEdgeGraph<DataTypes>, ReadError>
+where
+    DataTypes: Data + Default,
+    DataTypes::Vertex: VertexPosition,
+    R: BufRead,
+{
+    let mut tokens = Tokens {
+        reader,
+        line: VecDeque::new(),
+    };
+
+    // The counts can be on the same line as the keyword
+    tokens.next_line()?;
+    let keyword: String = tokens.parse("keyword")?;
+    if !keyword.ends_with("OFF") {
+        return Err(ReadError::parse("missing OFF keyword"));
+    }
+    if tokens.line.is_empty() {
+        tokens.next_line()?;
+    }
+    let vertex_count: usize = tokens.parse("vertex count")?;
+    let face_count: usize = tokens.parse("face count")?;
+
+    let mut graph = HalfEdgeGraph::default();
+
+    // The counts comes from the file, so they aren't used to preallocate
+    let mut vertices = Vec::new();
+    for _ in 0..vertex_count {
+        tokens.next_line()?;
+        let position = [tokens.parse("x")?, tokens.parse("y")?, tokens.parse("z")?];
+        vertices.push(graph.new_vertex(DataTypes::Vertex::from_position(position)));
+    }
+
+    let mut polygons = Vec::new();
+    for _ in 0..face_count {
+        tokens.next_line()?;
+        let count: usize = tokens.parse("polygon size")?;
+        let polygon = (0..count)
+            .map(|_| {
+                let index: usize = tokens.parse("vertex index")?;
+                vertices
+                    .get(index)
+                    .copied()
+                    .ok_or_else(|| ReadError::parse(format!("invalid vertex index {}", index)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        polygons.push(polygon);
+    }
+
+    let import = graph.add_polygons(&polygons);
+    for (index, face) in import.faces.into_iter().enumerate() {
+        face.map_err(|error| ReadError::Face { index, error })?;
+    }
+
+    Ok(graph)
+}
+
+/// Writes the vertex positions and faces of the graph as a OFF file
+pub fn write<DataTypes, W>(graph: &HalfEdgeGraph<DataTypes>, mut writer: W) -> std::io::Result<()>
+where
+    DataTypes: Data,
+    DataTypes::Vertex: VertexPosition,
+    W: Write,
+{
+    writeln!(writer, "OFF")?;
+    writeln!(
+        writer,
+        "{} {} {}",
+        graph.vertices.len(),
+        graph.faces.len(),
+        graph.edges.len()
+    )?;
+
+    let mut indices = SecondaryMap::new();
+    for (index, (handle, vertex)) in graph.vertices.iter().enumerate() {
+        indices.insert(handle, index);
+        let [x, y, z] = vertex.data.position();
+        writeln!(writer, "{} {} {}", x, y, z)?;
+    }
+
+    for face in graph.iter_faces() {
+        let vertices: Vec<_> = face.vertices().map(|v| indices[v.handle()]).collect();
+        write!(writer, "{}", vertices.len())?;
+        for index in vertices {
+            write!(writer, " {}", index)?;
+        }
+        writeln!(writer)?;
+    }
+
+    writer.flush()
+}
@@ -0,0 +1,695 @@
+//! Stanford PLY format
+//!
+//! The `vertex` and `face` elements are read into vertices and faces, and the optional `edge` element
+//! (with `vertex1` and `vertex2` properties) into edges. All other elements are skipped.
+//! Which properties are read and written is decided by implementing [PlyData] for the graph [Data].
+//!
+//! ```
+//! use half_edge_graph::io::ply::{self, Format, PlyData, Properties, Property, ScalarType, Value};
+//! use half_edge_graph::HalfEdgeGraph;
+//!
+//! #[derive(Default)]
+//! struct Data;
+//! impl half_edge_graph::Data for Data {
+//!     type Face = ();
+//!     type Edge = ();
+//!     type HalfEdge = ();
+//!     type Vertex = [f32; 3];
+//! }
+//!
+//! impl PlyData for Data {
+//!     fn vertex_properties() -> Vec<Property> {
+//!         ["x", "y", "z"]
+//!             .iter()
+//!             .map(|name| Property::scalar(*name, ScalarType::Float))
+//!             .collect()
+//!     }
+//!     fn read_vertex(properties: &Properties) -> [f32; 3] {
+//!         let get = |name| properties.f64(name).unwrap_or(0.) as f32;
+//!         [get("x"), get("y"), get("z")]
+//!     }
+//!     fn write_vertex(vertex: &[f32; 3]) -> Vec<Value> {
+//!         vertex.iter().map(|v| Value::Float(*v as f64)).collect()
+//!     }
+//! }
+//!
+//! let mut graph = HalfEdgeGraph::<Data>::default();
+//! let v1 = graph.new_vertex([0., 0., 0.]);
+//! let v2 = graph.new_vertex([1., 0., 0.]);
+//! let v3 = graph.new_vertex([0., 1., 0.]);
+//! graph.new_face(&[v1, v2, v3], ()).unwrap();
+//!
+//! let mut file = Vec::new();
+//! ply::write(&graph, &mut file, Format::BinaryLittleEndian).unwrap();
+//! let read: HalfEdgeGraph<Data> = ply::read(&file[..]).unwrap();
+//! assert!(read.is_isomorphic(&graph));
+//! ```
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::io::{BufRead, Write};
+
+use slotmap::SecondaryMap;
+
+use super::ReadError;
+use crate::{Data, HalfEdgeGraph, VertexHandle};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+impl Format {
+    fn name(self) -> &'static str {
+        match self {
+            Format::Ascii => "ascii",
+            Format::BinaryLittleEndian => "binary_little_endian",
+            Format::BinaryBigEndian => "binary_big_endian",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScalarType {
+    Char,
+    UChar,
+    Short,
+    UShort,
+    Int,
+    UInt,
+    Float,
+    Double,
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => ScalarType::Char,
+            "uchar" | "uint8" => ScalarType::UChar,
+            "short" | "int16" => ScalarType::Short,
+            "ushort" | "uint16" => ScalarType::UShort,
+            "int" | "int32" => ScalarType::Int,
+            "uint" | "uint32" => ScalarType::UInt,
+            "float" | "float32" => ScalarType::Float,
+            "double" | "float64" => ScalarType::Double,
+            _ => return None,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ScalarType::Char => "char",
+            ScalarType::UChar => "uchar",
+            ScalarType::Short => "short",
+            ScalarType::UShort => "ushort",
+            ScalarType::Int => "int",
+            ScalarType::UInt => "uint",
+            ScalarType::Float => "float",
+            ScalarType::Double => "double",
+        }
+    }
+
+    fn is_float(self) -> bool {
+        matches!(self, ScalarType::Float | ScalarType::Double)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyType {
+    Scalar(ScalarType),
+    /// A list of `item`, prefixed with the length as a `count`
+    List {
+        count: ScalarType,
+        item: ScalarType,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Property {
+    pub name: String,
+    pub ty: PropertyType,
+}
+
+impl Property {
+    pub fn scalar(name: impl Into<String>, ty: ScalarType) -> Self {
+        Self {
+            name: name.into(),
+            ty: PropertyType::Scalar(ty),
+        }
+    }
+
+    pub fn list(name: impl Into<String>, count: ScalarType, item: ScalarType) -> Self {
+        Self {
+            name: name.into(),
+            ty: PropertyType::List { count, item },
+        }
+    }
+}
+
+/// The value of a property, integer types are read as [Value::Int] and floating point types as [Value::Float]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    List(Vec<Value>),
+}
+
+impl Value {
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(value) => Some(*value as f64),
+            Value::Float(value) => Some(*value),
+            Value::List(_) => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Int(value) => Some(*value),
+            Value::Float(value) => Some(*value as i64),
+            Value::List(_) => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+/// The properties of a single vertex, face or edge in the file
+pub struct Properties<'a> {
+    properties: &'a [Property],
+    values: &'a [Value],
+}
+
+impl<'a> Properties<'a> {
+    /// Returns the value of the property with `name`, if the element have it
+    pub fn get(&self, name: &str) -> Option<&'a Value> {
+        self.properties
+            .iter()
+            .position(|property| property.name == name)
+            .map(|index| &self.values[index])
+    }
+
+    pub fn f64(&self, name: &str) -> Option<f64> {
+        self.get(name)?.as_f64()
+    }
+
+    pub fn i64(&self, name: &str) -> Option<i64> {
+        self.get(name)?.as_i64()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'a Property, &'a Value)> {
+        self.properties.iter().zip(self.values)
+    }
+}
+
+/// Mapping between the data of a graph and the properties of the PLY elements
+///
+/// The values returned by the `write_*` functions must match the properties returned by the
+/// `*_properties` function, in the same order.
+pub trait PlyData: Data {
+    fn vertex_properties() -> Vec<Property>;
+    fn read_vertex(properties: &Properties) -> Self::Vertex;
+    fn write_vertex(vertex: &Self::Vertex) -> Vec<Value>;
+
+    /// Properties of faces, except for `vertex_indices`
+    fn face_properties() -> Vec<Property> {
+        Vec::new()
+    }
+    fn read_face(_properties: &Properties) -> Self::Face {
+        Default::default()
+    }
+    fn write_face(_face: &Self::Face) -> Vec<Value> {
+        Vec::new()
+    }
+
+    /// Properties of edges, except for `vertex1` & `vertex2`.
+    ///
+    /// The `edge` element is only written if there is any properties.
+    fn edge_properties() -> Vec<Property> {
+        Vec::new()
+    }
+    fn read_edge(_properties: &Properties) -> Self::Edge {
+        Default::default()
+    }
+    fn write_edge(_edge: &Self::Edge) -> Vec<Value> {
+        Vec::new()
+    }
+}
+
+struct ElementDef {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+struct Header {
+    format: Format,
+    elements: Vec<ElementDef>,
+}
+
+fn read_header(reader: &mut impl BufRead) -> Result<Header, ReadError> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = Vec::new();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Err(ReadError::parse("missing end_header"));
+        }
+        let line = String::from_utf8(line).map_err(|_| ReadError::parse("header isn't utf8"))?;
+        let line = line.trim().to_string();
+        if line == "end_header" {
+            break;
+        }
+        lines.push(line);
+    }
+
+    let mut lines = lines.into_iter();
+    if lines.next().as_deref() != Some("ply") {
+        return Err(ReadError::parse("missing ply magic"));
+    }
+
+    let mut format = None;
+    let mut elements: Vec<ElementDef> = Vec::new();
+    for line in lines {
+        let words: Vec<_> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["format", name, _version] => {
+                format = Some(match *name {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::BinaryLittleEndian,
+                    "binary_big_endian" => Format::BinaryBigEndian,
+                    _ => return Err(ReadError::parse(format!("unknown format {}", name))),
+                });
+            }
+            ["element", name, count] => elements.push(ElementDef {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| ReadError::parse(format!("invalid element count {}", count)))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, name] => {
+                let property = Property::list(*name, parse_type(count)?, parse_type(item)?);
+                elements
+                    .last_mut()
+                    .ok_or_else(|| ReadError::parse("property before element"))?
+                    .properties
+                    .push(property);
+            }
+            ["property", ty, name] => {
+                let property = Property::scalar(*name, parse_type(ty)?);
+                elements
+                    .last_mut()
+                    .ok_or_else(|| ReadError::parse("property before element"))?
+                    .properties
+                    .push(property);
+            }
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            _ => return Err(ReadError::parse(format!("invalid header line '{}'", line))),
+        }
+    }
+
+    Ok(Header {
+        format: format.ok_or_else(|| ReadError::parse("missing format"))?,
+        elements,
+    })
+}
+
+fn parse_type(name: &str) -> Result<ScalarType, ReadError> {
+    ScalarType::parse(name).ok_or_else(|| ReadError::parse(format!("unknown type {}", name)))
+}
+
+/// Reads the values after the header
+struct BodyReader<R> {
+    reader: R,
+    format: Format,
+    tokens: VecDeque<String>,
+}
+
+macro_rules! read_binary {
+    ($self:ident, $type:ident) => {{
+        let mut bytes = [0; std::mem::size_of::<$type>()];
+        $self.reader.read_exact(&mut bytes)?;
+        if $self.format == Format::BinaryLittleEndian {
+            $type::from_le_bytes(bytes)
+        } else {
+            $type::from_be_bytes(bytes)
+        }
+    }};
+}
+
+impl<R: BufRead> BodyReader<R> {
+    fn token(&mut self) -> Result<String, ReadError> {
+        while self.tokens.is_empty() {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(ReadError::parse("unexpected end of file"));
+            }
+            self.tokens
+                .extend(line.split_whitespace().map(str::to_string));
+        }
+        Ok(self.tokens.pop_front().unwrap())
+    }
+
+    fn scalar(&mut self, ty: ScalarType) -> Result<Value, ReadError> {
+        if self.format == Format::Ascii {
+            let token = self.token()?;
+            let invalid = || ReadError::parse(format!("invalid {} '{}'", ty.name(), token));
+            return if ty.is_float() {
+                token.parse().map(Value::Float).map_err(|_| invalid())
+            } else {
+                token.parse().map(Value::Int).map_err(|_| invalid())
+            };
+        }
+
+        Ok(match ty {
+            ScalarType::Char => Value::Int(read_binary!(self, i8) as i64),
+            ScalarType::UChar => Value::Int(read_binary!(self, u8) as i64),
+            ScalarType::Short => Value::Int(read_binary!(self, i16) as i64),
+            ScalarType::UShort => Value::Int(read_binary!(self, u16) as i64),
+            ScalarType::Int => Value::Int(read_binary!(self, i32) as i64),
+            ScalarType::UInt => Value::Int(read_binary!(self, u32) as i64),
+            ScalarType::Float => Value::Float(read_binary!(self, f32) as f64),
+            ScalarType::Double => Value::Float(read_binary!(self, f64)),
+        })
+    }
+
+    fn property(&mut self, ty: PropertyType) -> Result<Value, ReadError> {
+        match ty {
+            PropertyType::Scalar(ty) => self.scalar(ty),
+            PropertyType::List { count, item } => {
+                let count = self
+                    .scalar(count)?
+                    .as_i64()
+                    .filter(|count| *count >= 0)
+                    .ok_or_else(|| ReadError::parse("invalid list length"))?;
+                (0..count)
+                    .map(|_| self.scalar(item))
+                    .collect::<Result<_, _>>()
+                    .map(Value::List)
+            }
+        }
+    }
+
+    fn element(&mut self, element: &ElementDef) -> Result<Vec<Value>, ReadError> {
+        element
+            .properties
+            .iter()
+            .map(|property| self.property(property.ty))
+            .collect()
+    }
+}
+
+fn vertex_index(
+    vertices: &[VertexHandle],
+    value: Option<&Value>,
+    name: &str,
+) -> Result<VertexHandle, ReadError> {
+    value
+        .and_then(Value::as_i64)
+        .and_then(|index| usize::try_from(index).ok())
+        .and_then(|index| vertices.get(index).copied())
+        .ok_or_else(|| ReadError::parse(format!("invalid {}", name)))
+}
+
+/// Reads a graph from a PLY file.
+///
+/// Faces are added with [HalfEdgeGraph::add_polygons], so edges shared by more than 2 faces
+/// are glued instead of failing.
+pub fn read<DataTypes: PlyData + Default, R: BufRead>(
+    mut reader: R,
+) -> Result<HalfEdgeGraph<DataTypes>, ReadError> {
+    let header = read_header(&mut reader)?;
+    let mut body = BodyReader {
+        reader,
+        format: header.format,
+        tokens: VecDeque::new(),
+    };
+
+    let mut graph = HalfEdgeGraph::default();
+    let mut vertices = Vec::new();
+
+    for element in &header.elements {
+        match element.name.as_str() {
+            "vertex" => {
+                for _ in 0..element.count {
+                    let values = body.element(element)?;
+                    let data = DataTypes::read_vertex(&Properties {
+                        properties: &element.properties,
+                        values: &values,
+                    });
+                    vertices.push(graph.new_vertex(data));
+                }
+            }
+            "face" => {
+                // The count comes from the file, so it isn't used to preallocate
+                let mut polygons = Vec::new();
+                let mut data = Vec::new();
+                for _ in 0..element.count {
+                    let values = body.element(element)?;
+                    let properties = Properties {
+                        properties: &element.properties,
+                        values: &values,
+                    };
+
+                    let indices = properties
+                        .get("vertex_indices")
+                        .or_else(|| properties.get("vertex_index"))
+                        .and_then(Value::as_list)
+                        .ok_or_else(|| ReadError::parse("face without vertex_indices"))?;
+                    let polygon = indices
+                        .iter()
+                        .map(|index| vertex_index(&vertices, Some(index), "vertex index"))
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    polygons.push(polygon);
+                    data.push(DataTypes::read_face(&properties));
+                }
+
+                let import = graph.add_polygons(&polygons);
+                for (index, (face, data)) in import.faces.into_iter().zip(data).enumerate() {
+                    let face = face.map_err(|error| ReadError::Face { index, error })?;
+                    graph.faces[face].data = data;
+                }
+            }
+            "edge" => {
+                let mut used = SecondaryMap::new();
+                for _ in 0..element.count {
+                    let values = body.element(element)?;
+                    let properties = Properties {
+                        properties: &element.properties,
+                        values: &values,
+                    };
+
+                    let v1 = vertex_index(&vertices, properties.get("vertex1"), "vertex1")?;
+                    let v2 = vertex_index(&vertices, properties.get("vertex2"), "vertex2")?;
+                    let data = DataTypes::read_edge(&properties);
+
+                    // Rows are matched to the edges the faces created, each edge is only
+                    // used once so parallel edges & self loops keep their own data
+                    let existing = graph
+                        .find_edges(v1, v2)
+                        .find(|edge| !used.contains_key(*edge));
+                    let edge = match existing {
+                        Some(edge) => {
+                            graph.edges[edge].data = data;
+                            edge
+                        }
+                        None => graph
+                            .new_edge(v1, v2, data)
+                            .ok_or_else(|| ReadError::parse("invalid edge"))?,
+                    };
+                    used.insert(edge, ());
+                }
+            }
+            _ => {
+                for _ in 0..element.count {
+                    body.element(element)?;
+                }
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+fn write_element_header(
+    writer: &mut impl Write,
+    name: &str,
+    count: usize,
+    properties: &[Property],
+) -> std::io::Result<()> {
+    writeln!(writer, "element {} {}", name, count)?;
+    for property in properties {
+        match property.ty {
+            PropertyType::Scalar(ty) => {
+                writeln!(writer, "property {} {}", ty.name(), property.name)?
+            }
+            PropertyType::List { count, item } => writeln!(
+                writer,
+                "property list {} {} {}",
+                count.name(),
+                item.name(),
+                property.name
+            )?,
+        }
+    }
+    Ok(())
+}
+
+macro_rules! write_binary {
+    ($writer:ident, $format:ident, $value:expr, $type:ident) => {{
+        let value = $value as $type;
+        if $format == Format::BinaryLittleEndian {
+            $writer.write_all(&value.to_le_bytes())
+        } else {
+            $writer.write_all(&value.to_be_bytes())
+        }
+    }};
+}
+
+fn write_scalar(
+    writer: &mut impl Write,
+    format: Format,
+    ty: ScalarType,
+    value: &Value,
+) -> std::io::Result<()> {
+    let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidInput, "list given for scalar");
+    if format == Format::Ascii {
+        return match (ty.is_float(), value) {
+            (true, value) => write!(writer, "{} ", value.as_f64().ok_or_else(invalid)?),
+            (false, value) => write!(writer, "{} ", value.as_i64().ok_or_else(invalid)?),
+        };
+    }
+
+    let int = || value.as_i64().ok_or_else(invalid);
+    let float = || value.as_f64().ok_or_else(invalid);
+    match ty {
+        ScalarType::Char => write_binary!(writer, format, int()?, i8),
+        ScalarType::UChar => write_binary!(writer, format, int()?, u8),
+        ScalarType::Short => write_binary!(writer, format, int()?, i16),
+        ScalarType::UShort => write_binary!(writer, format, int()?, u16),
+        ScalarType::Int => write_binary!(writer, format, int()?, i32),
+        ScalarType::UInt => write_binary!(writer, format, int()?, u32),
+        ScalarType::Float => write_binary!(writer, format, float()?, f32),
+        ScalarType::Double => write_binary!(writer, format, float()?, f64),
+    }
+}
+
+fn write_element(
+    writer: &mut impl Write,
+    format: Format,
+    properties: &[Property],
+    values: &[Value],
+) -> std::io::Result<()> {
+    if properties.len() != values.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "number of values doesn't match the properties",
+        ));
+    }
+
+    for (property, value) in properties.iter().zip(values) {
+        match property.ty {
+            PropertyType::Scalar(ty) => write_scalar(writer, format, ty, value)?,
+            PropertyType::List { count, item } => {
+                let values = value.as_list().ok_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidInput, "scalar given for list")
+                })?;
+                write_scalar(writer, format, count, &Value::Int(values.len() as i64))?;
+                for value in values {
+                    write_scalar(writer, format, item, value)?;
+                }
+            }
+        }
+    }
+
+    if format == Format::Ascii {
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Writes the graph as a PLY file.
+///
+/// The `edge` element is only written if [PlyData::edge_properties] isn't empty.
+pub fn write<DataTypes: PlyData, W: Write>(
+    graph: &HalfEdgeGraph<DataTypes>,
+    mut writer: W,
+    format: Format,
+) -> std::io::Result<()> {
+    let vertex_properties = DataTypes::vertex_properties();
+
+    let longest_face = graph
+        .iter_faces()
+        .map(|face| face.vertices().count())
+        .max()
+        .unwrap_or(0);
+    let count_type = if longest_face <= u8::MAX as usize {
+        ScalarType::UChar
+    } else {
+        ScalarType::UInt
+    };
+    let mut face_properties = vec![Property::list(
+        "vertex_indices",
+        count_type,
+        ScalarType::Int,
+    )];
+    face_properties.extend(DataTypes::face_properties());
+
+    let mut edge_properties = DataTypes::edge_properties();
+    if !edge_properties.is_empty() {
+        edge_properties.insert(0, Property::scalar("vertex1", ScalarType::Int));
+        edge_properties.insert(1, Property::scalar("vertex2", ScalarType::Int));
+    }
+
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format {} 1.0", format.name())?;
+    write_element_header(
+        &mut writer,
+        "vertex",
+        graph.vertices.len(),
+        &vertex_properties,
+    )?;
+    write_element_header(&mut writer, "face", graph.faces.len(), &face_properties)?;
+    if !edge_properties.is_empty() {
+        write_element_header(&mut writer, "edge", graph.edges.len(), &edge_properties)?;
+    }
+    writeln!(writer, "end_header")?;
+
+    let mut indices = SecondaryMap::new();
+    for (index, (handle, vertex)) in graph.vertices.iter().enumerate() {
+        indices.insert(handle, index);
+        let values = DataTypes::write_vertex(&vertex.data);
+        write_element(&mut writer, format, &vertex_properties, &values)?;
+    }
+
+    for face in graph.iter_faces() {
+        let polygon = face
+            .vertices()
+            .map(|vertex| Value::Int(indices[vertex.handle()] as i64))
+            .collect();
+        let mut values = vec![Value::List(polygon)];
+        values.extend(DataTypes::write_face(&face));
+        write_element(&mut writer, format, &face_properties, &values)?;
+    }
+
+    if !edge_properties.is_empty() {
+        for edge in graph.iter_edges() {
+            let [v1, v2] = edge.vertices();
+            let mut values = vec![
+                Value::Int(indices[v1.handle()] as i64),
+                Value::Int(indices[v2.handle()] as i64),
+            ];
+            values.extend(DataTypes::write_edge(&edge));
+            write_element(&mut writer, format, &edge_properties, &values)?;
+        }
+    }
+
+    writer.flush()
+}
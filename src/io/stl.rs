@@ -0,0 +1,287 @@
+//! STL format, ascii and binary
+//!
+//! STL files are triangle soups, every triangle have its own copy of its vertices. So when reading
+//! the vertices are welded together, see [Welding], to get a connected graph.
+//! When writing, faces with more than 3 vertices are split into a fan of triangles.
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use super::{ReadError, VertexPosition};
+use crate::{Data, HalfEdgeGraph, VertexHandle};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ascii,
+    Binary,
+}
+
+/// How the vertices of the triangles are merged when reading
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Welding {
+    /// Only vertices with exactly the same position are merged
+    Exact,
+    /// Vertices closer than the distance are merged, using a spatial hash.
+    /// The distance must be positive and finite.
+    ///
+    /// A vertex is merged with the first vertex found within the distance, so chains of vertices
+    /// that are each within the distance of the next are not necessarily merged into one.
+    Epsilon(f64),
+}
+
+/// Merges positions into vertices
+struct Welder {
+    welding: Welding,
+    cells: HashMap<[i64; 3], Vec<(VertexHandle, [f64; 3])>>,
+}
+
+impl Welder {
+    fn cell(&self, position: [f64; 3]) -> [i64; 3] {
+        match self.welding {
+            // Normalize -0 to 0, so they are merged
+            Welding::Exact => position.map(|p| (p + 0.0).to_bits() as i64),
+            Welding::Epsilon(epsilon) => position.map(|p| (p / epsilon).floor() as i64),
+        }
+    }
+
+    fn find(&self, position: [f64; 3]) -> Option<VertexHandle> {
+        let cell = self.cell(position);
+        let epsilon = match self.welding {
+            Welding::Exact => {
+                return self.cells.get(&cell).map(|vertices| vertices[0].0);
+            }
+            Welding::Epsilon(epsilon) => epsilon,
+        };
+
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    // The cells of huge coordinates are saturated, and so are their neighbors
+                    let neighbor = [
+                        cell[0].saturating_add(dx),
+                        cell[1].saturating_add(dy),
+                        cell[2].saturating_add(dz),
+                    ];
+                    let found = self.cells.get(&neighbor).and_then(|vertices| {
+                        vertices.iter().find(|(_, other)| {
+                            let distance: f64 =
+                                (0..3).map(|i| (other[i] - position[i]).powi(2)).sum();
+                            distance.sqrt() <= epsilon
+                        })
+                    });
+                    if let Some((vertex, _)) = found {
+                        return Some(*vertex);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn weld<DataTypes: Data>(
+        &mut self,
+        graph: &mut HalfEdgeGraph<DataTypes>,
+        position: [f64; 3],
+    ) -> VertexHandle
+    where
+        DataTypes::Vertex: VertexPosition,
+    {
+        if let Some(vertex) = self.find(position) {
+            return vertex;
+        }
+
+        let vertex = graph.new_vertex(DataTypes::Vertex::from_position(position));
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push((vertex, position));
+        vertex
+    }
+}
+
+fn read_triangles(bytes: &[u8]) -> Result<Vec<[[f64; 3]; 3]>, ReadError> {
+    // Binary files can also start with "solid", so use the size to detect them
+    if bytes.len() >= 84 {
+        let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+        if bytes.len() == 84 + count * 50 {
+            let float = |offset: usize| {
+                let b = &bytes[offset..offset + 4];
+                f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64
+            };
+
+            return Ok((0..count)
+                .map(|triangle| {
+                    // Skip the normal
+                    let start = 84 + triangle * 50 + 12;
+                    let vertex = |i: usize| {
+                        let offset = start + i * 12;
+                        [float(offset), float(offset + 4), float(offset + 8)]
+                    };
+                    [vertex(0), vertex(1), vertex(2)]
+                })
+                .collect());
+        }
+    }
+
+    let text = std::str::from_utf8(bytes).map_err(|_| ReadError::parse("invalid ascii stl"))?;
+    if !text.trim_start().starts_with("solid") {
+        return Err(ReadError::parse("missing solid keyword"));
+    }
+
+    let mut tokens = text.split_whitespace();
+    let mut vertices = Vec::new();
+    while let Some(token) = tokens.next() {
+        if token == "vertex" {
+            let mut coordinate = || -> Result<f64, ReadError> {
+                let token = tokens
+                    .next()
+                    .ok_or_else(|| ReadError::parse("missing coordinate"))?;
+                token
+                    .parse()
+                    .map_err(|_| ReadError::parse(format!("invalid coordinate '{}'", token)))
+            };
+            vertices.push([coordinate()?, coordinate()?, coordinate()?]);
+        }
+    }
+
+    if vertices.len() % 3 != 0 {
+        return Err(ReadError::parse("facet without 3 vertices"));
+    }
+    Ok(vertices
+        .chunks(3)
+        .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+        .collect())
+}
+
+/// Reads a graph from a STL file, ascii or binary.
+///
+/// Triangles that are degenerate after welding are skipped.
+/// Fails if a coordinate isn't finite, or if the [Welding::Epsilon] distance isn't positive.
+/// Faces are added with [HalfEdgeGraph::add_polygons], so edges shared by more than 2 faces
+/// are glued instead of failing.
+pub fn read<DataTypes, R>(
+    mut reader: R,
+    welding: Welding,
+) -> Result<HalfEdgeGraph<DataTypes>, ReadError>
+where
+    DataTypes: Data + Default,
+    DataTypes::Vertex: VertexPosition,
+    R: Read,
+{
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let triangles = read_triangles(&bytes)?;
+
+    if let Welding::Epsilon(epsilon) = welding {
+        if !(epsilon > 0. && epsilon.is_finite()) {
+            return Err(ReadError::parse(format!(
+                "invalid welding distance {}",
+                epsilon
+            )));
+        }
+    }
+    if let Some(index) = triangles
+        .iter()
+        .position(|triangle| triangle.iter().flatten().any(|c| !c.is_finite()))
+    {
+        return Err(ReadError::parse(format!(
+            "triangle {} has a coordinate that isn't finite",
+            index
+        )));
+    }
+
+    let mut graph = HalfEdgeGraph::default();
+    let mut welder = Welder {
+        welding,
+        cells: HashMap::new(),
+    };
+
+    // Keep the index of each triangle in the file, for errors
+    let (indices, polygons): (Vec<_>, Vec<_>) = triangles
+        .iter()
+        .map(|triangle| triangle.map(|position| welder.weld(&mut graph, position)))
+        .enumerate()
+        .filter(|(_, [a, b, c])| a != b && b != c && c != a)
+        .unzip();
+
+    let import = graph.add_polygons(&polygons);
+    for (index, face) in indices.into_iter().zip(import.faces) {
+        face.map_err(|error| ReadError::Face { index, error })?;
+    }
+
+    Ok(graph)
+}
+
+fn normal(triangle: &[[f64; 3]; 3]) -> [f64; 3] {
+    let [a, b, c] = triangle;
+    let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let n = [
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ];
+    let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+    if length > 0. {
+        n.map(|n| n / length)
+    } else {
+        [0.; 3]
+    }
+}
+
+/// Writes the faces of the graph as a STL file, faces are triangulated as a fan from their first vertex.
+pub fn write<DataTypes, W>(
+    graph: &HalfEdgeGraph<DataTypes>,
+    mut writer: W,
+    format: Format,
+) -> std::io::Result<()>
+where
+    DataTypes: Data,
+    DataTypes::Vertex: VertexPosition,
+    W: Write,
+{
+    let triangles: Vec<[[f64; 3]; 3]> = graph
+        .iter_faces()
+        .flat_map(|face| {
+            let positions: Vec<_> = face
+                .vertices()
+                .map(|v| VertexPosition::position(&*v))
+                .collect();
+            (1..positions.len().saturating_sub(1))
+                .map(|i| [positions[0], positions[i], positions[i + 1]])
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    match format {
+        Format::Ascii => {
+            writeln!(writer, "solid half_edge_graph")?;
+            for triangle in &triangles {
+                let [nx, ny, nz] = normal(triangle);
+                writeln!(writer, "facet normal {} {} {}", nx, ny, nz)?;
+                writeln!(writer, "outer loop")?;
+                for [x, y, z] in triangle {
+                    writeln!(writer, "vertex {} {} {}", x, y, z)?;
+                }
+                writeln!(writer, "endloop")?;
+                writeln!(writer, "endfacet")?;
+            }
+            writeln!(writer, "endsolid half_edge_graph")?;
+        }
+        Format::Binary => {
+            let mut header = [0; 80];
+            let name = b"half_edge_graph";
+            header[..name.len()].copy_from_slice(name);
+            writer.write_all(&header)?;
+            writer.write_all(&(triangles.len() as u32).to_le_bytes())?;
+
+            for triangle in &triangles {
+                for vector in std::iter::once(&normal(triangle)).chain(triangle) {
+                    for value in vector {
+                        writer.write_all(&(*value as f32).to_le_bytes())?;
+                    }
+                }
+                writer.write_all(&0u16.to_le_bytes())?;
+            }
+        }
+    }
+
+    writer.flush()
+}
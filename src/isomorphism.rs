@@ -0,0 +1,187 @@
+//! Handle independent comparison of graphs
+//!
+//! Two graphs are isomorphic if there is a one to one mapping between their items,
+//! that preserves all links (pair, next, prev, vertex, edge & face).
+//!
+//! Since the links of a half edge decides the links of all its neighbors, fixing the mapping of one half edge
+//! fixes the mapping of its whole connected component. So the components are matched by trying to map a seed
+//! half edge to each candidate half edge in the other graph, and walking the component from there.
+use slotmap::{Key, SecondaryMap};
+
+use crate::{Data, EdgeHandle, FaceHandle, HalfEdgeGraph, HalfEdgeHandle, Handle, VertexHandle};
+
+/// Mapping from the handles of one graph, to the handles of another,
+/// see [HalfEdgeGraph::isomorphism]
+#[derive(Debug, Clone, Default)]
+pub struct HandleMap {
+    vertices: SecondaryMap<VertexHandle, VertexHandle>,
+    edges: SecondaryMap<EdgeHandle, EdgeHandle>,
+    half_edges: SecondaryMap<HalfEdgeHandle, HalfEdgeHandle>,
+    faces: SecondaryMap<FaceHandle, FaceHandle>,
+}
+
+impl HandleMap {
+    pub fn vertex(&self, handle: VertexHandle) -> Option<VertexHandle> {
+        self.vertices.get(handle).copied()
+    }
+
+    pub fn edge(&self, handle: EdgeHandle) -> Option<EdgeHandle> {
+        self.edges.get(handle).copied()
+    }
+
+    pub fn half_edge(&self, handle: HalfEdgeHandle) -> Option<HalfEdgeHandle> {
+        self.half_edges.get(handle).copied()
+    }
+
+    pub fn face(&self, handle: FaceHandle) -> Option<FaceHandle> {
+        self.faces.get(handle).copied()
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<Handle> {
+        match handle {
+            Handle::Vertex(handle) => self.vertex(handle).map(Handle::from),
+            Handle::Edge(handle) => self.edge(handle).map(Handle::from),
+            Handle::HalfEdge(handle) => self.half_edge(handle).map(Handle::from),
+            Handle::Face(handle) => self.face(handle).map(Handle::from),
+        }
+    }
+
+    fn extend(&mut self, other: HandleMap) {
+        self.vertices.extend(other.vertices);
+        self.edges.extend(other.edges);
+        self.half_edges.extend(other.half_edges);
+        self.faces.extend(other.faces);
+    }
+}
+
+/// Maps `a` to `b`, fails if either already is mapped to something else
+fn bind<K: Key>(
+    forward: &mut SecondaryMap<K, K>,
+    reverse: &mut SecondaryMap<K, K>,
+    a: K,
+    b: K,
+) -> bool {
+    match (forward.get(a), reverse.get(b)) {
+        (Some(mapped), Some(reversed)) => *mapped == b && *reversed == a,
+        (None, None) => {
+            forward.insert(a, b);
+            reverse.insert(b, a);
+            true
+        }
+        _ => false,
+    }
+}
+
+impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
+    /// Returns true if the graphs have the same structure, see [HalfEdgeGraph::isomorphism]
+    pub fn is_isomorphic<Other: Data>(&self, other: &HalfEdgeGraph<Other>) -> bool {
+        self.isomorphism(other).is_some()
+    }
+
+    /// Finds a mapping from the items of this graph to the items of `other`, that preserves all links.
+    ///
+    /// Only the structure is compared, not the data.
+    /// Each connected component is matched in `O(n * m)`, where `n` is the size of the component
+    /// and `m` the number of half edges in `other`.
+    pub fn isomorphism<Other: Data>(&self, other: &HalfEdgeGraph<Other>) -> Option<HandleMap> {
+        if self.vertices.len() != other.vertices.len()
+            || self.edges.len() != other.edges.len()
+            || self.half_edges.len() != other.half_edges.len()
+            || self.faces.len() != other.faces.len()
+        {
+            return None;
+        }
+
+        let mut result = HandleMap::default();
+        let mut used = SecondaryMap::new();
+
+        for seed in self.half_edges.keys() {
+            if result.half_edges.contains_key(seed) {
+                continue;
+            }
+
+            let component = other
+                .half_edges
+                .keys()
+                .filter(|candidate| !used.contains_key(*candidate))
+                .find_map(|candidate| self.match_component(other, seed, candidate))?;
+
+            for hedge in component.half_edges.values() {
+                used.insert(*hedge, ());
+            }
+            result.extend(component);
+        }
+
+        // Vertices without any edges
+        let isolated = other
+            .vertices
+            .iter()
+            .filter(|(_, vertex)| vertex.hedge.is_null());
+        for ((a, _), (b, _)) in self
+            .vertices
+            .iter()
+            .filter(|(_, vertex)| vertex.hedge.is_null())
+            .zip(isolated)
+        {
+            result.vertices.insert(a, b);
+        }
+
+        if result.vertices.len() == self.vertices.len() {
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    /// Tries to map the component of `seed` to the component of `candidate`
+    fn match_component<Other: Data>(
+        &self,
+        other: &HalfEdgeGraph<Other>,
+        seed: HalfEdgeHandle,
+        candidate: HalfEdgeHandle,
+    ) -> Option<HandleMap> {
+        let mut forward = HandleMap::default();
+        let mut reverse = HandleMap::default();
+
+        let mut stack = vec![(seed, candidate)];
+        while let Some((a, b)) = stack.pop() {
+            if let Some(mapped) = forward.half_edges.get(a) {
+                if *mapped != b {
+                    return None;
+                }
+                continue;
+            }
+            if !bind(&mut forward.half_edges, &mut reverse.half_edges, a, b) {
+                return None;
+            }
+
+            let a = &self.half_edges[a];
+            let b = &other.half_edges[b];
+
+            if !bind(
+                &mut forward.vertices,
+                &mut reverse.vertices,
+                a.vertex,
+                b.vertex,
+            ) || !bind(&mut forward.edges, &mut reverse.edges, a.edge, b.edge)
+            {
+                return None;
+            }
+
+            match (a.face.is_null(), b.face.is_null()) {
+                (true, true) => {}
+                (false, false) => {
+                    if !bind(&mut forward.faces, &mut reverse.faces, a.face, b.face) {
+                        return None;
+                    }
+                }
+                _ => return None,
+            }
+
+            stack.push((a.next, b.next));
+            stack.push((a.pair, b.pair));
+        }
+
+        Some(forward)
+    }
+}
@@ -262,6 +262,48 @@ macro_rules! create_iterator {
                 }
             }
 
+            /// Calls `f` for each item, like [Iterator::for_each]
+            pub fn for_each<F>(mut self, mut f: F)
+            where
+                F: FnMut($out_mut<'_, DataTypes>),
+            {
+                while let Some(item) = self.next() {
+                    f(item);
+                }
+            }
+
+            /// Calls `f` for each item, stopping at the first error, like [Iterator::try_for_each]
+            pub fn try_for_each<F, E>(mut self, mut f: F) -> Result<(), E>
+            where
+                F: FnMut($out_mut<'_, DataTypes>) -> Result<(), E>,
+            {
+                while let Some(item) = self.next() {
+                    f(item)?;
+                }
+                Ok(())
+            }
+
+            /// Folds every item into a accumulator, like [Iterator::fold]
+            pub fn fold<B, F>(mut self, init: B, mut f: F) -> B
+            where
+                F: FnMut(B, $out_mut<'_, DataTypes>) -> B,
+            {
+                let mut accumulator = init;
+                while let Some(item) = self.next() {
+                    accumulator = f(accumulator, item);
+                }
+                accumulator
+            }
+
+            /// Maps each item with `f`, and collects the results
+            pub fn map_collect<B, C, F>(mut self, mut f: F) -> C
+            where
+                F: FnMut($out_mut<'_, DataTypes>) -> B,
+                C: std::iter::FromIterator<B>,
+            {
+                std::iter::from_fn(|| self.next().map(&mut f)).collect()
+            }
+
             create_iterator!(
                 __impl_common;
                 init: $init;
@@ -0,0 +1,429 @@
+//! Undo & redo of graph mutations
+//!
+//! When the journal is enabled, every primitive mutation of the graph is recorded,
+//! new items, rewrites of the links between items and writes to item data
+//! (through [DerefMut](std::ops::DerefMut) on the *FnMut types and [IndexMut](std::ops::IndexMut)).
+//!
+//! The mutations are grouped into transactions, either explicitly with [HalfEdgeGraph::begin_transaction]
+//! & [HalfEdgeGraph::commit_transaction] (or [HalfEdgeGraph::transaction]), or implicitly where all mutations
+//! since the last transaction boundary forms one (unnamed) transaction.
+//!
+//! Only the first write to each item in a transaction is recorded, so a transaction stores
+//! the state of the items it touched from before the transaction, and the items it created.
+//!
+//! # Handles
+//! Undo & redo modifies items in place, so handles to items that existed before a transaction stays valid.
+//!
+//! Items created in a transaction are detached from the graph when it is undone (see [slotmap::SlotMap::detach]),
+//! and reattached with the same handle when it is redone, so handles to them are valid again after the redo.
+//! A downside of this is that the slots of undone items are never reused, even after the redo history is dropped.
+use std::collections::HashSet;
+
+use crate::{
+    Data, Edge, EdgeHandle, Face, FaceHandle, GraphEvent, HalfEdge, HalfEdgeGraph, HalfEdgeHandle,
+    Handle, Vertex, VertexHandle,
+};
+
+/// The links of a item, its connectivity without any data
+#[derive(Debug, Clone, Copy)]
+enum Links {
+    Vertex(HalfEdgeHandle),
+    Edge(HalfEdgeHandle),
+    Face(HalfEdgeHandle),
+    HalfEdge {
+        pair: HalfEdgeHandle,
+        next: HalfEdgeHandle,
+        prev: HalfEdgeHandle,
+        vertex: VertexHandle,
+        edge: EdgeHandle,
+        face: FaceHandle,
+    },
+}
+
+enum Element<DataTypes: Data> {
+    Vertex(Vertex<DataTypes::Vertex>),
+    Edge(Edge<DataTypes::Edge>),
+    HalfEdge(HalfEdge<DataTypes::HalfEdge>),
+    Face(Face<DataTypes::Face>),
+}
+
+enum Value<DataTypes: Data> {
+    Vertex(DataTypes::Vertex),
+    Edge(DataTypes::Edge),
+    HalfEdge(DataTypes::HalfEdge),
+    Face(DataTypes::Face),
+}
+
+struct Transaction<DataTypes: Data> {
+    name: String,
+
+    /// Items created by the transaction, the element is `Some` while the transaction is undone
+    created: Vec<(Handle, Option<Element<DataTypes>>)>,
+    /// Links of the items modified by the transaction, swapped with the graph on undo & redo
+    links: Vec<(Handle, Links)>,
+    /// Data of the items modified by the transaction, swapped with the graph on undo & redo
+    data: Vec<(Handle, Value<DataTypes>)>,
+
+    // Items that doesn't need to be recorded again while recording
+    linked: HashSet<Handle>,
+    written: HashSet<Handle>,
+}
+
+impl<DataTypes: Data> Transaction<DataTypes> {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            created: Vec::new(),
+            links: Vec::new(),
+            data: Vec::new(),
+            linked: HashSet::new(),
+            written: HashSet::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.links.is_empty() && self.data.is_empty()
+    }
+}
+
+struct CloneFns<DataTypes: Data> {
+    vertex: fn(&DataTypes::Vertex) -> DataTypes::Vertex,
+    edge: fn(&DataTypes::Edge) -> DataTypes::Edge,
+    half_edge: fn(&DataTypes::HalfEdge) -> DataTypes::HalfEdge,
+    face: fn(&DataTypes::Face) -> DataTypes::Face,
+}
+
+pub(crate) struct Journal<DataTypes: Data> {
+    undo: Vec<Transaction<DataTypes>>,
+    redo: Vec<Transaction<DataTypes>>,
+    current: Option<Transaction<DataTypes>>,
+    depth: usize,
+
+    clone: CloneFns<DataTypes>,
+}
+
+impl<DataTypes: Data> std::fmt::Debug for Journal<DataTypes> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Journal")
+            .field("undo", &self.undo.len())
+            .field("redo", &self.redo.len())
+            .field("depth", &self.depth)
+            .finish()
+    }
+}
+
+impl<DataTypes: Data> Journal<DataTypes> {
+    /// Returns the transaction new mutations should be recorded in
+    fn recording(&mut self) -> &mut Transaction<DataTypes> {
+        // Any new mutation makes the redo history invalid
+        self.redo.clear();
+        self.current
+            .get_or_insert_with(|| Transaction::new(String::new()))
+    }
+
+    /// Ends the current transaction, regardless of how many transactions that have begun
+    fn flush(&mut self) {
+        self.depth = 0;
+        if let Some(mut transaction) = self.current.take() {
+            if !transaction.is_empty() {
+                transaction.linked = HashSet::new();
+                transaction.written = HashSet::new();
+                self.undo.push(transaction);
+            }
+        }
+    }
+}
+
+impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
+    /// Starts recording mutations, so they can be undone.
+    ///
+    /// Does nothing if the journal already is enabled.
+    pub fn enable_journal(&mut self)
+    where
+        DataTypes::Vertex: Clone,
+        DataTypes::Edge: Clone,
+        DataTypes::HalfEdge: Clone,
+        DataTypes::Face: Clone,
+    {
+        if self.journal.is_some() {
+            return;
+        }
+
+        self.journal = Some(Box::new(Journal {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            current: None,
+            depth: 0,
+            clone: CloneFns {
+                vertex: Clone::clone,
+                edge: Clone::clone,
+                half_edge: Clone::clone,
+                face: Clone::clone,
+            },
+        }));
+    }
+
+    /// Stops recording mutations, and drops all undo & redo history.
+    pub fn disable_journal(&mut self) {
+        self.journal = None;
+    }
+
+    pub fn is_journal_enabled(&self) -> bool {
+        self.journal.is_some()
+    }
+
+    /// Begins a new transaction, all mutations until the matching [HalfEdgeGraph::commit_transaction]
+    /// are undone & redone together.
+    ///
+    /// Transactions can be nested, in which case the inner transactions are part of the outermost one.
+    pub fn begin_transaction(&mut self, name: impl Into<String>) {
+        if let Some(journal) = self.journal.as_deref_mut() {
+            if journal.depth == 0 {
+                journal.flush();
+                journal.current = Some(Transaction::new(name.into()));
+            }
+            journal.depth += 1;
+        }
+    }
+
+    /// Ends the transaction started by [HalfEdgeGraph::begin_transaction]
+    pub fn commit_transaction(&mut self) {
+        if let Some(journal) = self.journal.as_deref_mut() {
+            if journal.depth <= 1 {
+                journal.flush();
+            } else {
+                journal.depth -= 1;
+            }
+        }
+    }
+
+    /// Runs `f` in a transaction named `name`
+    pub fn transaction<R>(&mut self, name: impl Into<String>, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.begin_transaction(name);
+        let result = f(self);
+        self.commit_transaction();
+        result
+    }
+
+    /// Name of the transaction [HalfEdgeGraph::undo] would undo
+    pub fn undo_name(&self) -> Option<&str> {
+        let journal = self.journal.as_deref()?;
+        match &journal.current {
+            Some(current) if !current.is_empty() => Some(&current.name),
+            _ => journal
+                .undo
+                .last()
+                .map(|transaction| transaction.name.as_str()),
+        }
+    }
+
+    /// Name of the transaction [HalfEdgeGraph::redo] would redo
+    pub fn redo_name(&self) -> Option<&str> {
+        let journal = self.journal.as_deref()?;
+        journal
+            .redo
+            .last()
+            .map(|transaction| transaction.name.as_str())
+    }
+
+    /// Undoes the last transaction, any open transaction is committed first.
+    ///
+    /// Returns the name of the undone transaction, or `None` if there was nothing to undo.
+    pub fn undo(&mut self) -> Option<String> {
+        let mut journal = self.journal.take()?;
+        journal.flush();
+
+        let transaction = journal.undo.pop().map(|mut transaction| {
+            self.undo_transaction(&mut transaction);
+            let name = transaction.name.clone();
+            journal.redo.push(transaction);
+            name
+        });
+        self.journal = Some(journal);
+
+        #[cfg(test)]
+        self.verify_invarians();
+
+        transaction
+    }
+
+    /// Redoes the last undone transaction.
+    ///
+    /// Returns the name of the redone transaction, or `None` if there was nothing to redo.
+    pub fn redo(&mut self) -> Option<String> {
+        let mut journal = self.journal.take()?;
+        journal.flush();
+
+        let transaction = journal.redo.pop().map(|mut transaction| {
+            self.redo_transaction(&mut transaction);
+            let name = transaction.name.clone();
+            journal.undo.push(transaction);
+            name
+        });
+        self.journal = Some(journal);
+
+        #[cfg(test)]
+        self.verify_invarians();
+
+        transaction
+    }
+
+    /// See [HalfEdgeGraph::record_new]
+    pub(crate) fn journal_new(&mut self, handle: Handle) {
+        if let Some(journal) = self.journal.as_deref_mut() {
+            let transaction = journal.recording();
+            transaction.created.push((handle, None));
+            transaction.linked.insert(handle);
+            transaction.written.insert(handle);
+        }
+    }
+
+    /// See [HalfEdgeGraph::record_links]
+    pub(crate) fn journal_links(&mut self, handle: Handle) {
+        if self.journal.is_none() {
+            return;
+        }
+
+        let links = self.links(handle);
+        if let Some(journal) = self.journal.as_deref_mut() {
+            let transaction = journal.recording();
+            if transaction.linked.insert(handle) {
+                transaction.links.push((handle, links));
+            }
+        }
+    }
+
+    /// See [HalfEdgeGraph::record_data]
+    pub(crate) fn journal_data(&mut self, handle: Handle) {
+        let journal = match self.journal.as_deref_mut() {
+            Some(journal) => journal,
+            None => return,
+        };
+
+        if !journal.recording().written.insert(handle) {
+            return;
+        }
+
+        let clone = &journal.clone;
+        let value = match handle {
+            Handle::Vertex(h) => Value::Vertex((clone.vertex)(&self.vertices[h].data)),
+            Handle::Edge(h) => Value::Edge((clone.edge)(&self.edges[h].data)),
+            Handle::HalfEdge(h) => Value::HalfEdge((clone.half_edge)(&self.half_edges[h].data)),
+            Handle::Face(h) => Value::Face((clone.face)(&self.faces[h].data)),
+        };
+        journal.recording().data.push((handle, value));
+    }
+
+    fn undo_transaction(&mut self, transaction: &mut Transaction<DataTypes>) {
+        for (handle, links) in transaction.links.iter_mut().rev() {
+            *links = self.replace_links(*handle, *links);
+            self.emit_relinked(*handle);
+        }
+        for (handle, value) in transaction.data.iter_mut().rev() {
+            self.swap_data(*handle, value);
+            self.emit(GraphEvent::DataModified(*handle));
+        }
+        for (handle, element) in transaction.created.iter_mut().rev() {
+            *element = Some(self.detach_element(*handle));
+            self.emit_removed(*handle);
+        }
+    }
+
+    fn redo_transaction(&mut self, transaction: &mut Transaction<DataTypes>) {
+        for (handle, element) in &mut transaction.created {
+            self.reattach_element(*handle, element.take().unwrap());
+            self.emit_added(*handle);
+        }
+        for (handle, links) in &mut transaction.links {
+            *links = self.replace_links(*handle, *links);
+            self.emit_relinked(*handle);
+        }
+        for (handle, value) in &mut transaction.data {
+            self.swap_data(*handle, value);
+            self.emit(GraphEvent::DataModified(*handle));
+        }
+    }
+
+    fn links(&self, handle: Handle) -> Links {
+        match handle {
+            Handle::Vertex(h) => Links::Vertex(self.vertices[h].hedge),
+            Handle::Edge(h) => Links::Edge(self.edges[h].hedge),
+            Handle::Face(h) => Links::Face(self.faces[h].hedge),
+            Handle::HalfEdge(h) => {
+                let hedge = &self.half_edges[h];
+                Links::HalfEdge {
+                    pair: hedge.pair,
+                    next: hedge.next,
+                    prev: hedge.prev,
+                    vertex: hedge.vertex,
+                    edge: hedge.edge,
+                    face: hedge.face,
+                }
+            }
+        }
+    }
+
+    /// Sets the links of `handle`, and returns the previous links
+    fn replace_links(&mut self, handle: Handle, links: Links) -> Links {
+        let old = self.links(handle);
+        match (handle, links) {
+            (Handle::Vertex(h), Links::Vertex(hedge)) => self.vertices[h].hedge = hedge,
+            (Handle::Edge(h), Links::Edge(hedge)) => self.edges[h].hedge = hedge,
+            (Handle::Face(h), Links::Face(hedge)) => self.faces[h].hedge = hedge,
+            (
+                Handle::HalfEdge(h),
+                Links::HalfEdge {
+                    pair,
+                    next,
+                    prev,
+                    vertex,
+                    edge,
+                    face,
+                },
+            ) => {
+                let hedge = &mut self.half_edges[h];
+                hedge.pair = pair;
+                hedge.next = next;
+                hedge.prev = prev;
+                hedge.vertex = vertex;
+                hedge.edge = edge;
+                hedge.face = face;
+            }
+            _ => unreachable!("links doesn't match the handle"),
+        }
+        old
+    }
+
+    fn swap_data(&mut self, handle: Handle, value: &mut Value<DataTypes>) {
+        match (handle, value) {
+            (Handle::Vertex(h), Value::Vertex(data)) => {
+                std::mem::swap(&mut self.vertices[h].data, data)
+            }
+            (Handle::Edge(h), Value::Edge(data)) => std::mem::swap(&mut self.edges[h].data, data),
+            (Handle::HalfEdge(h), Value::HalfEdge(data)) => {
+                std::mem::swap(&mut self.half_edges[h].data, data)
+            }
+            (Handle::Face(h), Value::Face(data)) => std::mem::swap(&mut self.faces[h].data, data),
+            _ => unreachable!("data doesn't match the handle"),
+        }
+    }
+
+    fn detach_element(&mut self, handle: Handle) -> Element<DataTypes> {
+        match handle {
+            Handle::Vertex(h) => Element::Vertex(self.vertices.detach(h).unwrap()),
+            Handle::Edge(h) => Element::Edge(self.edges.detach(h).unwrap()),
+            Handle::HalfEdge(h) => Element::HalfEdge(self.half_edges.detach(h).unwrap()),
+            Handle::Face(h) => Element::Face(self.faces.detach(h).unwrap()),
+        }
+    }
+
+    fn reattach_element(&mut self, handle: Handle, element: Element<DataTypes>) {
+        match (handle, element) {
+            (Handle::Vertex(h), Element::Vertex(vertex)) => self.vertices.reattach(h, vertex),
+            (Handle::Edge(h), Element::Edge(edge)) => self.edges.reattach(h, edge),
+            (Handle::HalfEdge(h), Element::HalfEdge(hedge)) => self.half_edges.reattach(h, hedge),
+            (Handle::Face(h), Element::Face(face)) => self.faces.reattach(h, face),
+            _ => unreachable!("element doesn't match the handle"),
+        }
+    }
+}
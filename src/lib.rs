@@ -54,6 +54,14 @@
 //!     // do something with the vertex
 //!  }
 //! ```
+//! Or use the closure based functions on the iterators (`for_each`, `try_for_each`, `fold` & `map_collect`):
+//! ```no_run
+//! # let mut graph = half_edge_graph::HalfEdgeGraph::<()>::default();
+//! # let face_handle = half_edge_graph::FaceHandle::default();
+//!  graph.face_mut(face_handle).unwrap().vertices_mut().for_each(|vertex| {
+//!     // do something with the vertex
+//!  });
+//! ```
 //! This will probably improve once GAT's & LendingIterator are stabilized.
 //!
 //! ## Function Set - Data
@@ -68,6 +76,25 @@
 //!
 //! Instead I choose to implement it using [slotmap::SlotMap::iter_mut], but this makes it unsound to access any
 //! links, and only mutable access to the data can be provided.
+//!
+//! # Undo & Redo
+//! When enabled with [HalfEdgeGraph::enable_journal] the graph records all mutations, grouped into
+//! transactions, which can be undone with [HalfEdgeGraph::undo] and redone with [HalfEdgeGraph::redo].
+//!
+//! # Events
+//! When enabled with [HalfEdgeGraph::enable_events] the graph queues a [GraphEvent] for every mutation,
+//! use [HalfEdgeGraph::drain_events] to keep caches of the graph up to date.
+//!
+//! # Features
+//!  - `petgraph` implements the [petgraph](https://docs.rs/petgraph) graph traits for [HalfEdgeGraph],
+//!    and conversions to and from `petgraph::Graph`
+//!  - `geometry` enables the [geometry] module, with normals, areas, curvature etc for graphs
+//!    with vertex positions
+//!  - `rayon` adds parallel iteration over the items in the graph, using [rayon](https://docs.rs/rayon)
+//!  - `proptest` enables the [proptest] module, with [proptest](https://docs.rs/proptest)
+//!    strategies for random valid graphs and random sequences of operations
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::fmt::Debug;
 
 use itertools::Itertools;
@@ -86,6 +113,54 @@ pub use iterators::{
     VertexOutHalfEdgesMut, VertexVertex, VertexVertexMut, VerticesMut,
 };
 
+mod events;
+pub use events::GraphEvent;
+
+mod isomorphism;
+pub use isomorphism::HandleMap;
+
+mod journal;
+
+mod manifold;
+
+mod orientation;
+
+mod planar;
+
+mod planarity;
+pub use planarity::EmbeddingError;
+
+mod polygons;
+pub use polygons::{GluedEdge, PolygonImport};
+
+#[cfg(feature = "geometry")]
+pub mod geometry;
+
+pub mod debug;
+pub mod io;
+pub mod primitives;
+pub mod render;
+
+mod update;
+pub use update::NeighborView;
+
+mod validate;
+pub use validate::InvariantError;
+
+#[cfg(feature = "rayon")]
+mod parallel;
+
+#[cfg(feature = "petgraph")]
+mod petgraph;
+#[cfg(feature = "petgraph")]
+pub use crate::petgraph::{
+    EdgeReference, EdgeReferences, VertexEdgeReferences, VertexIdentifiers, VertexNeighbors,
+    VertexReferences,
+};
+
+#[cfg(feature = "proptest")]
+pub mod proptest;
+
 #[cfg(test)]
 mod test;
 
@@ -129,11 +204,159 @@ impl VertexHandle {
     }
 }
 
+/// Handle to any item in the graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Handle {
+    Vertex(VertexHandle),
+    Edge(EdgeHandle),
+    HalfEdge(HalfEdgeHandle),
+    Face(FaceHandle),
+}
+
+macro_rules! impl_handle_conversion {
+    ($($variant:ident: $handle:ty),*) => {
+        $(
+            impl From<$handle> for Handle {
+                fn from(handle: $handle) -> Self {
+                    Handle::$variant(handle)
+                }
+            }
+
+            impl TryFrom<Handle> for $handle {
+                type Error = Handle;
+
+                fn try_from(handle: Handle) -> Result<Self, Self::Error> {
+                    match handle {
+                        Handle::$variant(handle) => Ok(handle),
+                        _ => Err(handle),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_handle_conversion!(
+    Vertex: VertexHandle,
+    Edge: EdgeHandle,
+    HalfEdge: HalfEdgeHandle,
+    Face: FaceHandle
+);
+
+/// Reasons why [HalfEdgeGraph::new_face_from_half_edges] can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaceError {
+    /// No half edges was given
+    Empty,
+    /// The half edge doesn't exist in the graph
+    InvalidHalfEdge(HalfEdgeHandle),
+    /// The vertex doesn't exist in the graph
+    InvalidVertex(VertexHandle),
+    /// The face doesn't exist in the graph
+    InvalidFace(FaceHandle),
+    /// The vertex is followed by itself, and [Data::ALLOW_SELF_LOOPS] is false
+    SelfLoop(VertexHandle),
+    /// The half edge is given more than once
+    Duplicate(HalfEdgeHandle),
+    /// The half edge already is part of a face
+    HasFace(HalfEdgeHandle),
+    /// `hedge` doesn't end at the vertex `next` starts at
+    NotChained {
+        hedge: HalfEdgeHandle,
+        next: HalfEdgeHandle,
+    },
+    /// `hedge` & `next` can't be made adjacent, since there is no free half edge
+    /// around their shared vertex to move the other edges to
+    NotAdjacent {
+        hedge: HalfEdgeHandle,
+        next: HalfEdgeHandle,
+    },
+    /// A new edge can't be inserted at the vertex, since it is surrounded by faces
+    NoFreeHalfEdge(VertexHandle),
+}
+
+impl std::fmt::Display for FaceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FaceError::Empty => write!(f, "face have no half edges"),
+            FaceError::InvalidHalfEdge(hedge) => write!(f, "half edge {:?} doesn't exist", hedge),
+            FaceError::InvalidVertex(vertex) => write!(f, "vertex {:?} doesn't exist", vertex),
+            FaceError::InvalidFace(face) => write!(f, "face {:?} doesn't exist", face),
+            FaceError::SelfLoop(vertex) => write!(f, "vertex {:?} follows itself", vertex),
+            FaceError::Duplicate(hedge) => write!(f, "half edge {:?} is used twice", hedge),
+            FaceError::HasFace(hedge) => write!(f, "half edge {:?} already have a face", hedge),
+            FaceError::NotChained { hedge, next } => write!(
+                f,
+                "half edge {:?} doesn't end where {:?} starts",
+                hedge, next
+            ),
+            FaceError::NotAdjacent { hedge, next } => write!(
+                f,
+                "half edges {:?} and {:?} can't be made adjacent",
+                hedge, next
+            ),
+            FaceError::NoFreeHalfEdge(vertex) => {
+                write!(f, "vertex {:?} is surrounded by faces", vertex)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FaceError {}
+
+/// Changes to the `next` & `prev` links of half edges, on top of the links in the graph
+#[derive(Default)]
+struct PlannedLinks {
+    next: HashMap<HalfEdgeHandle, HalfEdgeHandle>,
+    prev: HashMap<HalfEdgeHandle, HalfEdgeHandle>,
+    order: Vec<(HalfEdgeHandle, HalfEdgeHandle)>,
+    /// Half edges whose face is removed before the links are applied, so they count as free
+    freed: HashSet<HalfEdgeHandle>,
+}
+
+impl PlannedLinks {
+    fn next<DataTypes: Data>(
+        &self,
+        graph: &HalfEdgeGraph<DataTypes>,
+        hedge: HalfEdgeHandle,
+    ) -> HalfEdgeHandle {
+        self.next
+            .get(&hedge)
+            .copied()
+            .unwrap_or(graph.half_edges[hedge].next)
+    }
+
+    fn prev<DataTypes: Data>(
+        &self,
+        graph: &HalfEdgeGraph<DataTypes>,
+        hedge: HalfEdgeHandle,
+    ) -> HalfEdgeHandle {
+        self.prev
+            .get(&hedge)
+            .copied()
+            .unwrap_or(graph.half_edges[hedge].prev)
+    }
+
+    fn link(&mut self, prev: HalfEdgeHandle, next: HalfEdgeHandle) {
+        self.next.insert(prev, next);
+        self.prev.insert(next, prev);
+        self.order.push((prev, next));
+    }
+}
+
 pub trait Data {
     type Face: Default;
     type Edge: Default;
     type HalfEdge: Default;
     type Vertex: Default;
+
+    /// Allow multiple edges between the same pair of vertices
+    const ALLOW_PARALLEL_EDGES: bool = false;
+    /// Allow edges from a vertex to itself
+    ///
+    /// Both half edges of a self loop goes out from the vertex, so the loop is visited twice
+    /// when iterating over the fan of the vertex.
+    const ALLOW_SELF_LOOPS: bool = false;
 }
 
 impl Data for () {
@@ -143,7 +366,7 @@ impl Data for () {
     type Vertex = ();
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq)]
 struct HalfEdge<Data> {
     data: Data,
 
@@ -155,19 +378,19 @@ struct HalfEdge<Data> {
     edge: EdgeHandle,
     face: FaceHandle,
 }
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq)]
 struct Face<Data> {
     data: Data,
     hedge: HalfEdgeHandle,
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq)]
 struct Edge<Data> {
     data: Data,
     hedge: HalfEdgeHandle,
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq)]
 struct Vertex<Data> {
     data: Data,
     hedge: HalfEdgeHandle,
@@ -180,6 +403,59 @@ pub struct HalfEdgeGraph<DataTypes: Data> {
     edges: slotmap::SlotMap<EdgeHandle, Edge<DataTypes::Edge>>,
     vertices: slotmap::SlotMap<VertexHandle, Vertex<DataTypes::Vertex>>,
     data: DataTypes,
+
+    journal: Option<Box<journal::Journal<DataTypes>>>,
+    events: Option<Vec<GraphEvent>>,
+}
+
+/// Clones all items, with the same handles.
+///
+/// The journal and the event queue are not cloned, they are disabled for the new graph.
+impl<DataTypes> Clone for HalfEdgeGraph<DataTypes>
+where
+    DataTypes: Data + Clone,
+    DataTypes::Face: Clone,
+    DataTypes::Edge: Clone,
+    DataTypes::HalfEdge: Clone,
+    DataTypes::Vertex: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            half_edges: self.half_edges.clone(),
+            faces: self.faces.clone(),
+            edges: self.edges.clone(),
+            vertices: self.vertices.clone(),
+            data: self.data.clone(),
+            journal: None,
+            events: None,
+        }
+    }
+}
+
+fn slotmap_eq<K: Key, V: PartialEq>(
+    a: &slotmap::SlotMap<K, V>,
+    b: &slotmap::SlotMap<K, V>,
+) -> bool {
+    a.len() == b.len() && a.iter().all(|(key, value)| b.get(key) == Some(value))
+}
+
+/// Exact equality, the graphs must have the same items with the same handles, links and data.
+///
+/// See [HalfEdgeGraph::is_isomorphic] for comparing graphs independent of the handles.
+impl<DataTypes> PartialEq for HalfEdgeGraph<DataTypes>
+where
+    DataTypes: Data,
+    DataTypes::Face: PartialEq,
+    DataTypes::Edge: PartialEq,
+    DataTypes::HalfEdge: PartialEq,
+    DataTypes::Vertex: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        slotmap_eq(&self.half_edges, &other.half_edges)
+            && slotmap_eq(&self.faces, &other.faces)
+            && slotmap_eq(&self.edges, &other.edges)
+            && slotmap_eq(&self.vertices, &other.vertices)
+    }
 }
 
 impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
@@ -188,7 +464,9 @@ impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
             data,
             ..Default::default()
         };
-        self.vertices.insert(vertex)
+        let handle = self.vertices.insert(vertex);
+        self.record_new(handle);
+        handle
     }
 
     pub fn new_edge(
@@ -197,10 +475,10 @@ impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
         v2: VertexHandle,
         data: DataTypes::Edge,
     ) -> Option<EdgeHandle> {
-        if v1 == v2 {
+        if v1 == v2 && !DataTypes::ALLOW_SELF_LOOPS {
             return None;
         }
-        if self.find_edge(v1, v2).is_some() {
+        if !DataTypes::ALLOW_PARALLEL_EDGES && self.find_edge(v1, v2).is_some() {
             // Only 1 edge is allowed between vertexes
             return None;
         }
@@ -220,9 +498,28 @@ impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
             .transpose()
             .ok()?;
 
+        Some(self.insert_edge(v1, v2, data, v1_insertion, v2_insertion))
+    }
+
+    /// Creates a new edge, and splice it into the fans of `v1` & `v2`.
+    ///
+    /// The new half edges are inserted before the `*_insertion` out half edges (which must be free),
+    /// or becomes the vertex half edge if `None`.
+    /// For self loops both insertions must be the same.
+    fn insert_edge(
+        &mut self,
+        v1: VertexHandle,
+        v2: VertexHandle,
+        data: DataTypes::Edge,
+        v1_insertion: Option<HalfEdgeHandle>,
+        v2_insertion: Option<HalfEdgeHandle>,
+    ) -> EdgeHandle {
         let e = self.edges.insert(Default::default());
         let h1 = self.half_edges.insert(Default::default());
         let h2 = self.half_edges.insert(Default::default());
+        self.record_new(e);
+        self.record_new(h1);
+        self.record_new(h2);
 
         self.edges[e] = Edge { data, hedge: h1 };
 
@@ -247,20 +544,30 @@ impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
             face: FaceHandle::null(),
         };
 
+        if v1 == v2 && v1_insertion.is_none() {
+            // A self loop on a vertex without edges, each half edge is a fan by itself
+            debug_assert!(v2_insertion.is_none());
+            self.link_hedges(h1, h1);
+            self.link_hedges(h2, h2);
+            self.set_vertex_hedge(v1, h1);
+
+            #[cfg(test)]
+            self.verify_invarians();
+
+            return e;
+        }
+
         if let Some(v1_insertion) = v1_insertion {
             debug_assert_eq!(self.half_edge(v1_insertion).unwrap().pair().vertex(), v1);
 
             let e1 = v1_insertion;
             let e2 = self.half_edges[e1].prev;
 
-            self.half_edges[e2].next = h1;
-            self.half_edges[h1].prev = e2;
-
-            self.half_edges[h2].next = e1;
-            self.half_edges[e1].prev = h2;
+            self.link_hedges(e2, h1);
+            self.link_hedges(h2, e1);
         } else {
             debug_assert!(self.vertices[v1].hedge.is_null());
-            self.vertices[v1].hedge = h1;
+            self.set_vertex_hedge(v1, h1);
         }
 
         if let Some(v2_insertion) = v2_insertion {
@@ -269,20 +576,17 @@ impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
             let e1 = v2_insertion;
             let e2 = self.half_edges[e1].prev;
 
-            self.half_edges[h1].next = e1;
-            self.half_edges[e1].prev = h1;
-
-            self.half_edges[e2].next = h2;
-            self.half_edges[h2].prev = e2;
+            self.link_hedges(h1, e1);
+            self.link_hedges(e2, h2);
         } else {
             debug_assert!(self.vertices[v2].hedge.is_null());
-            self.vertices[v2].hedge = h2;
+            self.set_vertex_hedge(v2, h2);
         }
 
         #[cfg(test)]
         self.verify_invarians();
 
-        Some(e)
+        e
     }
 
     pub fn new_face(&mut self, v: &[VertexHandle], data: DataTypes::Face) -> Option<FaceHandle> {
@@ -297,30 +601,68 @@ impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
             debug_assert!(self.half_edges[*hedges.last().unwrap()].vertex == *v2);
         }
 
-        for hedge in &hedges {
-            if !self.half_edges[*hedge].face.is_null() {
-                return None;
+        self.new_face_from_half_edges(&hedges, data).ok()
+    }
+
+    /// Creates a new face from a loop of existing half edges, where each half edge
+    /// ends at the vertex the next one starts at.
+    ///
+    /// Unlike [HalfEdgeGraph::new_face] this can be used to select which of multiple parallel
+    /// edges (or self loops) should be part of the face, and doesn't need to search for the half edges.
+    ///
+    /// The graph isn't modified if an error is returned.
+    pub fn new_face_from_half_edges(
+        &mut self,
+        hedges: &[HalfEdgeHandle],
+        data: DataTypes::Face,
+    ) -> Result<FaceHandle, FaceError> {
+        if hedges.is_empty() {
+            return Err(FaceError::Empty);
+        }
+
+        let mut seen = HashSet::with_capacity(hedges.len());
+        for &hedge in hedges {
+            let half_edge = self
+                .half_edges
+                .get(hedge)
+                .ok_or(FaceError::InvalidHalfEdge(hedge))?;
+            if !seen.insert(hedge) {
+                return Err(FaceError::Duplicate(hedge));
+            }
+            if !half_edge.face.is_null() {
+                return Err(FaceError::HasFace(hedge));
+            }
+        }
+
+        for (&hedge, &next) in hedges.iter().circular_tuple_windows() {
+            let next_pair = self.half_edges[next].pair;
+            if self.half_edges[hedge].vertex != self.half_edges[next_pair].vertex {
+                return Err(FaceError::NotChained { hedge, next });
             }
         }
 
-        // Try to make all half edges adjecent
-        for (h1, h2) in hedges.iter().circular_tuple_windows() {
-            self.make_hedge_adjencent(*h1, *h2)?;
+        // Check that all half edges can be made adjecent before moving any of them
+        let mut links = PlannedLinks::default();
+        for (&hedge, &next) in hedges.iter().circular_tuple_windows() {
+            self.plan_hedge_adjacent(hedge, next, &seen, &mut links)
+                .ok_or(FaceError::NotAdjacent { hedge, next })?;
         }
+        self.apply_links(links);
 
         let face = self.faces.insert(Face {
-            hedge: *hedges.first().unwrap(),
+            hedge: hedges[0],
             data,
         });
+        self.record_new(face);
 
-        for hedge in hedges {
-            self.half_edges[hedge].face = face
+        for &hedge in hedges {
+            self.set_hedge_face(hedge, face);
         }
 
         #[cfg(test)]
         self.verify_invarians();
 
-        Some(face)
+        Ok(face)
     }
 
     pub fn find_edge(&self, v1: VertexHandle, v2: VertexHandle) -> Option<EdgeHandle> {
@@ -328,6 +670,33 @@ impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
             .map(|hedge| self.half_edges[hedge].edge)
     }
 
+    /// Finds all edges between `v1` & `v2`, see [Data::ALLOW_PARALLEL_EDGES]
+    pub fn find_edges(
+        &self,
+        v1: VertexHandle,
+        v2: VertexHandle,
+    ) -> impl Iterator<Item = EdgeHandle> + '_ {
+        self.find_half_edges(v1, v2)
+            // Both half edges of a self loop goes from v1 to v1, only report it once
+            .filter(move |hedge| {
+                v1 != v2 || self.edges[self.half_edges[*hedge].edge].hedge == *hedge
+            })
+            .map(move |hedge| self.half_edges[hedge].edge)
+    }
+
+    /// Finds all half edges going from `v1` to `v2`
+    pub fn find_half_edges(
+        &self,
+        v1: VertexHandle,
+        v2: VertexHandle,
+    ) -> impl Iterator<Item = HalfEdgeHandle> + '_ {
+        self.vertex(v1)
+            .into_iter()
+            .flat_map(|vertex| vertex.out_half_edges())
+            .filter(move |hedge| hedge.vertex() == v2)
+            .map(|hedge| hedge.handle())
+    }
+
     pub fn find_half_edge(&self, v1: VertexHandle, v2: VertexHandle) -> Option<HalfEdgeHandle> {
         let vertex = self.vertex(v1)?;
 
@@ -373,52 +742,135 @@ impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
         &self,
         after: HalfEdgeHandle,
         before: HalfEdgeHandle,
+        reserved: &HashSet<HalfEdgeHandle>,
+        links: &PlannedLinks,
     ) -> Option<HalfEdgeHandle> {
         debug_assert_eq!(
             self.half_edges[before].vertex,
             self.half_edges[after].vertex
         );
 
-        let mut current = self.half_edge(after).unwrap();
+        let mut current = after;
         loop {
             if current == before {
                 return None;
             }
 
-            if current.face().is_none() {
-                return Some(current.handle());
+            let free = self.half_edges[current].face.is_null() || links.freed.contains(&current);
+            if free && !reserved.contains(&current) {
+                return Some(current);
             }
-            current = current.next().pair();
+            current = self.half_edges[links.next(self, current)].pair;
         }
     }
 
-    fn make_hedge_adjencent(&mut self, r#in: HalfEdgeHandle, out: HalfEdgeHandle) -> Option<()> {
+    /// Plans making `out` follow `r#in`, by moving the other edges around their shared vertex to a free half edge.
+    /// The relinking is added to `links` instead of modifying the graph, so multiple half edges
+    /// can be checked before any is moved.
+    ///
+    /// The `reserved` half edges are about to get a face, so they are not free even though
+    /// they don't have a face yet.
+    fn plan_hedge_adjacent(
+        &self,
+        r#in: HalfEdgeHandle,
+        out: HalfEdgeHandle,
+        reserved: &HashSet<HalfEdgeHandle>,
+        links: &mut PlannedLinks,
+    ) -> Option<()> {
         let out_pair = self.half_edges[out].pair;
         debug_assert_eq!(
             self.half_edges[r#in].vertex,
             self.half_edges[out_pair].vertex
         );
 
-        if self.half_edges[r#in].next == out {
+        let in_next = links.next(self, r#in);
+        if in_next == out {
             return Some(());
         }
 
-        let in_next = self.half_edges[r#in].next;
-        let out_prev = self.half_edges[out].prev;
+        let out_prev = links.prev(self, out);
 
-        let free_in = self.find_free_half_edge_between(out_pair, r#in)?;
-        let free_in_next = self.half_edges[free_in].next;
+        let free_in = self.find_free_half_edge_between(out_pair, r#in, reserved, links)?;
+        let free_in_next = links.next(self, free_in);
 
-        self.half_edges[r#in].next = out;
-        self.half_edges[out].prev = r#in;
+        links.link(r#in, out);
+        links.link(free_in, in_next);
+        links.link(out_prev, free_in_next);
 
-        self.half_edges[free_in].next = in_next;
-        self.half_edges[in_next].prev = free_in;
+        Some(())
+    }
 
-        self.half_edges[out_prev].next = free_in_next;
-        self.half_edges[free_in_next].prev = out_prev;
+    fn apply_links(&mut self, links: PlannedLinks) {
+        for (prev, next) in links.order {
+            self.link_hedges(prev, next);
+        }
+    }
 
-        Some(())
+    /// Returns true if any mutations needs to be recorded
+    pub(crate) fn is_recording(&self) -> bool {
+        self.journal.is_some() || self.events.is_some()
+    }
+
+    /// Records that `handle` was created, must be called directly after it is inserted
+    pub(crate) fn record_new(&mut self, handle: impl Into<Handle>) {
+        if !self.is_recording() {
+            return;
+        }
+        let handle = handle.into();
+        self.journal_new(handle);
+        self.emit_added(handle);
+    }
+
+    /// Records the links of `handle`, must be called before they are modified
+    pub(crate) fn record_links(&mut self, handle: impl Into<Handle>) {
+        if !self.is_recording() {
+            return;
+        }
+        let handle = handle.into();
+        self.journal_links(handle);
+        self.emit_relinked(handle);
+    }
+
+    /// Records the data of `handle`, must be called before it is modified
+    pub(crate) fn record_data(&mut self, handle: impl Into<Handle>) {
+        if !self.is_recording() {
+            return;
+        }
+        let handle = handle.into();
+        self.journal_data(handle);
+        self.emit(GraphEvent::DataModified(handle));
+    }
+
+    // All modifications of the links between existing items goes through these,
+    // so they can be recorded
+
+    /// Links `prev` & `next`, so `next` follows `prev`
+    fn link_hedges(&mut self, prev: HalfEdgeHandle, next: HalfEdgeHandle) {
+        self.record_links(prev);
+        self.record_links(next);
+
+        self.half_edges[prev].next = next;
+        self.half_edges[next].prev = prev;
+    }
+
+    fn set_hedge_face(&mut self, hedge: HalfEdgeHandle, face: FaceHandle) {
+        self.record_links(hedge);
+        self.half_edges[hedge].face = face;
+    }
+
+    fn set_hedge_vertex(&mut self, hedge: HalfEdgeHandle, vertex: VertexHandle) {
+        self.record_links(hedge);
+        self.half_edges[hedge].vertex = vertex;
+    }
+
+    fn set_face_hedge(&mut self, face: FaceHandle, hedge: HalfEdgeHandle) {
+        self.record_links(face);
+        self.faces[face].hedge = hedge;
+    }
+
+    fn set_vertex_hedge(&mut self, vertex: VertexHandle, hedge: HalfEdgeHandle) {
+        self.record_links(vertex);
+        self.vertices[vertex].hedge = hedge;
     }
 
     pub fn vertex(&self, handle: VertexHandle) -> Option<VertexFn<'_, DataTypes>> {
@@ -488,13 +940,89 @@ impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
         }
     }
 
+    /// Mutable access to the data of multiple vertices at once.
+    ///
+    /// Returns `None` if any handle is invalid, or if the same handle occurs more than once.
+    pub fn get_disjoint_vertices_mut<const N: usize>(
+        &mut self,
+        handles: [VertexHandle; N],
+    ) -> Option<[&mut DataTypes::Vertex; N]> {
+        if self.is_recording() {
+            self.vertices.get_disjoint_mut(handles)?;
+            for handle in handles {
+                self.record_data(handle);
+            }
+        }
+
+        self.vertices
+            .get_disjoint_mut(handles)
+            .map(|items| items.map(|item| &mut item.data))
+    }
+
+    /// Mutable access to the data of multiple half edges at once.
+    ///
+    /// Returns `None` if any handle is invalid, or if the same handle occurs more than once.
+    pub fn get_disjoint_half_edges_mut<const N: usize>(
+        &mut self,
+        handles: [HalfEdgeHandle; N],
+    ) -> Option<[&mut DataTypes::HalfEdge; N]> {
+        if self.is_recording() {
+            self.half_edges.get_disjoint_mut(handles)?;
+            for handle in handles {
+                self.record_data(handle);
+            }
+        }
+
+        self.half_edges
+            .get_disjoint_mut(handles)
+            .map(|items| items.map(|item| &mut item.data))
+    }
+
+    /// Mutable access to the data of multiple edges at once.
+    ///
+    /// Returns `None` if any handle is invalid, or if the same handle occurs more than once.
+    pub fn get_disjoint_edges_mut<const N: usize>(
+        &mut self,
+        handles: [EdgeHandle; N],
+    ) -> Option<[&mut DataTypes::Edge; N]> {
+        if self.is_recording() {
+            self.edges.get_disjoint_mut(handles)?;
+            for handle in handles {
+                self.record_data(handle);
+            }
+        }
+
+        self.edges
+            .get_disjoint_mut(handles)
+            .map(|items| items.map(|item| &mut item.data))
+    }
+
+    /// Mutable access to the data of multiple faces at once.
+    ///
+    /// Returns `None` if any handle is invalid, or if the same handle occurs more than once.
+    pub fn get_disjoint_faces_mut<const N: usize>(
+        &mut self,
+        handles: [FaceHandle; N],
+    ) -> Option<[&mut DataTypes::Face; N]> {
+        if self.is_recording() {
+            self.faces.get_disjoint_mut(handles)?;
+            for handle in handles {
+                self.record_data(handle);
+            }
+        }
+
+        self.faces
+            .get_disjoint_mut(handles)
+            .map(|items| items.map(|item| &mut item.data))
+    }
+
     pub fn iter_vertices(&self) -> impl Iterator<Item = VertexFn<'_, DataTypes>> {
         self.vertices
             .keys()
             .map(move |handle| VertexFn::new(self, handle))
     }
 
-    pub fn iter_edges(&self) -> impl Iterator<Item = EdgeFn<DataTypes>> {
+    pub fn iter_edges(&self) -> impl Iterator<Item = EdgeFn<'_, DataTypes>> {
         self.edges
             .keys()
             .map(move |handle| EdgeFn::new(self, handle))
@@ -539,6 +1067,7 @@ impl<DataTypes: Data> std::ops::Index<VertexHandle> for HalfEdgeGraph<DataTypes>
 
 impl<DataTypes: Data> std::ops::IndexMut<VertexHandle> for HalfEdgeGraph<DataTypes> {
     fn index_mut(&mut self, index: VertexHandle) -> &mut Self::Output {
+        self.record_data(index);
         &mut self.vertices.get_mut(index).unwrap().data
     }
 }
@@ -553,6 +1082,7 @@ impl<DataTypes: Data> std::ops::Index<HalfEdgeHandle> for HalfEdgeGraph<DataType
 
 impl<DataTypes: Data> std::ops::IndexMut<HalfEdgeHandle> for HalfEdgeGraph<DataTypes> {
     fn index_mut(&mut self, index: HalfEdgeHandle) -> &mut Self::Output {
+        self.record_data(index);
         &mut self.half_edges.get_mut(index).unwrap().data
     }
 }
@@ -567,6 +1097,7 @@ impl<DataTypes: Data> std::ops::Index<EdgeHandle> for HalfEdgeGraph<DataTypes> {
 
 impl<DataTypes: Data> std::ops::IndexMut<EdgeHandle> for HalfEdgeGraph<DataTypes> {
     fn index_mut(&mut self, index: EdgeHandle) -> &mut Self::Output {
+        self.record_data(index);
         &mut self.edges.get_mut(index).unwrap().data
     }
 }
@@ -581,6 +1112,7 @@ impl<DataTypes: Data> std::ops::Index<FaceHandle> for HalfEdgeGraph<DataTypes> {
 
 impl<DataTypes: Data> std::ops::IndexMut<FaceHandle> for HalfEdgeGraph<DataTypes> {
     fn index_mut(&mut self, index: FaceHandle) -> &mut Self::Output {
+        self.record_data(index);
         &mut self.faces.get_mut(index).unwrap().data
     }
 }
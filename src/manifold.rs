@@ -0,0 +1,118 @@
+//! Detection and repair of non-manifold vertices
+//!
+//! The out half edges of a vertex form a single cycle (`hedge -> hedge.pair().next()`), and the faces
+//! between them are grouped into fans, a run of consecutive faces without any gaps.
+//! A manifold vertex has at most one fan, while a non-manifold vertex ("bowtie") have multiple fans
+//! that only touch at the vertex. New faces can't always be added at such vertices, since
+//! the order of the fans around the vertex is arbitrary.
+use std::collections::HashMap;
+
+use crate::{Data, HalfEdgeGraph, HalfEdgeHandle, VertexHandle};
+
+impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
+    /// Returns all vertices where the faces form more than one fan.
+    pub fn non_manifold_vertices(&self) -> Vec<VertexHandle> {
+        self.vertices
+            .keys()
+            .filter(|vertex| self.fans(*vertex).len() > 1)
+            .collect()
+    }
+
+    /// Splits all non-manifold vertices, so each fan gets its own vertex.
+    ///
+    /// The first fan around the vertex, and all edges without faces, stays with the original vertex.
+    /// Returns a mapping from each new vertex to the vertex it was split from, the data of the
+    /// new vertex is cloned from the original.
+    pub fn split_non_manifold_vertices(&mut self) -> HashMap<VertexHandle, VertexHandle>
+    where
+        DataTypes::Vertex: Clone,
+    {
+        let mut mapping = HashMap::new();
+
+        for vertex in self.non_manifold_vertices() {
+            let fans = self.fans(vertex);
+
+            for fan in &fans[1..] {
+                let new_vertex = self.new_vertex(self.vertices[vertex].data.clone());
+                self.move_fan(fan, new_vertex);
+                mapping.insert(new_vertex, vertex);
+            }
+
+            if self.vertices[vertex].hedge != fans[0][0] {
+                self.set_vertex_hedge(vertex, fans[0][0]);
+            }
+        }
+
+        #[cfg(test)]
+        self.verify_invarians();
+
+        mapping
+    }
+
+    /// The out half edges of each fan around `vertex`, in order.
+    ///
+    /// A half edge is part of a fan if there is a face on either side of it.
+    fn fans(&self, vertex: VertexHandle) -> Vec<Vec<HalfEdgeHandle>> {
+        let first = self.vertices[vertex].hedge;
+        if first.is_null() {
+            return Vec::new();
+        }
+
+        let mut out = Vec::new();
+        let mut hedge = first;
+        loop {
+            out.push(hedge);
+            hedge = self.half_edges[self.half_edges[hedge].pair].next;
+            if hedge == first {
+                break;
+            }
+        }
+
+        // The face between out[i] and out[i + 1]
+        let has_face = |i: usize| {
+            let pair = self.half_edges[out[i % out.len()]].pair;
+            !self.half_edges[pair].face.is_null()
+        };
+
+        // Start directly after a gap, so no fan wraps around
+        let start = match (0..out.len()).find(|i| !has_face(*i)) {
+            Some(gap) => gap + 1,
+            None => return vec![out],
+        };
+
+        let mut fans: Vec<Vec<HalfEdgeHandle>> = Vec::new();
+        let mut in_fan = false;
+        for i in start..start + out.len() {
+            if has_face(i) {
+                if !in_fan {
+                    fans.push(vec![out[i % out.len()]]);
+                }
+                fans.last_mut().unwrap().push(out[(i + 1) % out.len()]);
+                in_fan = true;
+            } else {
+                in_fan = false;
+            }
+        }
+
+        fans
+    }
+
+    /// Moves the out half edges of `fan` to `vertex`, the fan must be bounded by gaps on both sides.
+    fn move_fan(&mut self, fan: &[HalfEdgeHandle], vertex: VertexHandle) {
+        let first = fan[0];
+        let last_pair = self.half_edges[*fan.last().unwrap()].pair;
+
+        // Both of these are in half edges without faces
+        let before = self.half_edges[first].prev;
+        let after = self.half_edges[last_pair].next;
+
+        self.link_hedges(last_pair, first);
+        self.link_hedges(before, after);
+
+        for &hedge in fan {
+            let pair = self.half_edges[hedge].pair;
+            self.set_hedge_vertex(pair, vertex);
+        }
+        self.set_vertex_hedge(vertex, first);
+    }
+}
@@ -0,0 +1,123 @@
+//! Reversing the winding of faces
+//!
+//! A face is the loop of half edges going around it, so reversing a face moves it to the
+//! pairs of its half edges. For a single face this is only possible if the pairs are free,
+//! since the winding of neighboring faces must agree. A whole component can always be reversed.
+use std::collections::HashSet;
+
+use itertools::Itertools;
+
+use crate::{Data, FaceError, FaceHandle, HalfEdgeGraph, HalfEdgeHandle, PlannedLinks};
+
+impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
+    /// Reverses the winding of `face`, the face keeps its handle and data.
+    ///
+    /// Fails with [FaceError::HasFace] if a neighboring face shares a edge with `face`,
+    /// since the neighbor would then have the opposite winding, use [HalfEdgeGraph::reverse_component] instead.
+    /// Fails with [FaceError::NotAdjacent] if the pairs can't be linked around a vertex,
+    /// the graph is unchanged on failure.
+    pub fn reverse_face(&mut self, face: FaceHandle) -> Result<(), FaceError> {
+        let first = self
+            .faces
+            .get(face)
+            .ok_or(FaceError::InvalidFace(face))?
+            .hedge;
+
+        let mut hedges = vec![first];
+        let mut hedge = self.half_edges[first].next;
+        while hedge != first {
+            hedges.push(hedge);
+            hedge = self.half_edges[hedge].next;
+        }
+
+        let pairs: Vec<_> = hedges
+            .iter()
+            .rev()
+            .map(|hedge| self.half_edges[*hedge].pair)
+            .collect();
+        if let Some(pair) = pairs
+            .iter()
+            .find(|pair| !self.half_edges[**pair].face.is_null())
+        {
+            return Err(FaceError::HasFace(*pair));
+        }
+
+        // Plan the relinking before anything is changed, the old half edges are free by then
+        let reserved = pairs.iter().copied().collect();
+        let mut links = PlannedLinks {
+            freed: hedges.iter().copied().collect(),
+            ..Default::default()
+        };
+        for (&r#in, &out) in pairs.iter().circular_tuple_windows() {
+            self.plan_hedge_adjacent(r#in, out, &reserved, &mut links)
+                .ok_or(FaceError::NotAdjacent {
+                    hedge: r#in,
+                    next: out,
+                })?;
+        }
+
+        for &hedge in &hedges {
+            self.set_hedge_face(hedge, FaceHandle::null());
+        }
+        self.apply_links(links);
+
+        for &pair in &pairs {
+            self.set_hedge_face(pair, face);
+        }
+        self.set_face_hedge(face, pairs[0]);
+
+        #[cfg(test)]
+        self.verify_invarians();
+
+        Ok(())
+    }
+
+    /// Reverses the winding of all faces in the connected component of `seed`.
+    ///
+    /// The component includes everything reachable by walking along half edges, so faces that only
+    /// share a vertex with the component are reversed too.
+    pub fn reverse_component(&mut self, seed: FaceHandle) -> Result<(), FaceError> {
+        let first = self
+            .faces
+            .get(seed)
+            .ok_or(FaceError::InvalidFace(seed))?
+            .hedge;
+
+        let mut component = HashSet::new();
+        let mut stack = vec![first];
+        while let Some(hedge) = stack.pop() {
+            if component.insert(hedge) {
+                stack.push(self.half_edges[hedge].next);
+                stack.push(self.half_edges[hedge].pair);
+            }
+        }
+
+        // Each loop `a -> b -> c` becomes `c' -> b' -> a'` (where ' is the pair)
+        let links: Vec<(HalfEdgeHandle, HalfEdgeHandle, FaceHandle)> = component
+            .iter()
+            .map(|&hedge| {
+                let pair = &self.half_edges[self.half_edges[hedge].pair];
+                let next = self.half_edges[pair.prev].pair;
+                (hedge, next, pair.face)
+            })
+            .collect();
+
+        let mut faces = Vec::new();
+        for (hedge, next, face) in links {
+            self.link_hedges(hedge, next);
+            self.set_hedge_face(hedge, face);
+
+            if !face.is_null() && self.faces[face].hedge == self.half_edges[hedge].pair {
+                faces.push((face, hedge));
+            }
+        }
+        for (face, hedge) in faces {
+            self.set_face_hedge(face, hedge);
+        }
+
+        #[cfg(test)]
+        self.verify_invarians();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+//! Parallel iteration using [rayon]
+//!
+//! The shared *Fn types only need a shared reference to the graph, so they can be used from multiple threads,
+//! the `par_iter_*` functions collects all handles and iterates over them in parallel.
+//!
+//! For mutable access only the data can be accessed (similar to the *FnData types), since the links
+//! of a item would allow reaching items that other threads are modifying.
+use rayon::prelude::*;
+
+use crate::{
+    Data, EdgeFn, EdgeHandle, FaceFn, FaceHandle, HalfEdgeFn, HalfEdgeGraph, HalfEdgeHandle,
+    VertexFn, VertexHandle,
+};
+
+macro_rules! par_iter {
+    (
+        $(#[$meta:meta])*
+        fn $name:ident -> $fn_type:ident;
+
+        $(#[$meta_mut:meta])*
+        fn $name_mut:ident(&mut $handle:ident) -> $type:ident;
+
+        map: $map:ident;
+    ) => {
+        $(#[$meta])*
+        pub fn $name(&self) -> impl IndexedParallelIterator<Item = $fn_type<'_, DataTypes>>
+        where
+            Self: Sync,
+        {
+            let handles: Vec<_> = self.$map.keys().collect();
+            handles
+                .into_par_iter()
+                .map(move |handle| $fn_type::new(self, handle))
+        }
+
+        $(#[$meta_mut])*
+        pub fn $name_mut<F>(&mut self, f: F)
+        where
+            DataTypes::$type: Send,
+            F: Fn($handle, &mut DataTypes::$type) + Sync + Send,
+        {
+            if self.is_recording() {
+                let handles: Vec<_> = self.$map.keys().collect();
+                for handle in handles {
+                    self.record_data(handle);
+                }
+            }
+
+            let items: Vec<_> = self.$map.iter_mut().collect();
+            items
+                .into_par_iter()
+                .for_each(|(handle, item)| f(handle, &mut item.data));
+        }
+    };
+}
+
+impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
+    par_iter!(
+        /// Parallel version of [HalfEdgeGraph::iter_vertices]
+        fn par_iter_vertices -> VertexFn;
+        /// Calls `f` with the data of every vertex in parallel
+        fn par_for_each_vertex_data_mut(&mut VertexHandle) -> Vertex;
+        map: vertices;
+    );
+
+    par_iter!(
+        /// Parallel version of [HalfEdgeGraph::iter_edges]
+        fn par_iter_edges -> EdgeFn;
+        /// Calls `f` with the data of every edge in parallel
+        fn par_for_each_edge_data_mut(&mut EdgeHandle) -> Edge;
+        map: edges;
+    );
+
+    par_iter!(
+        /// Parallel version of [HalfEdgeGraph::iter_half_edges]
+        fn par_iter_half_edges -> HalfEdgeFn;
+        /// Calls `f` with the data of every half edge in parallel
+        fn par_for_each_half_edge_data_mut(&mut HalfEdgeHandle) -> HalfEdge;
+        map: half_edges;
+    );
+
+    par_iter!(
+        /// Parallel version of [HalfEdgeGraph::iter_faces]
+        fn par_iter_faces -> FaceFn;
+        /// Calls `f` with the data of every face in parallel
+        fn par_for_each_face_data_mut(&mut FaceHandle) -> Face;
+        map: faces;
+    );
+}
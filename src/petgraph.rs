@@ -0,0 +1,352 @@
+//! Implementation of the [petgraph](::petgraph) graph traits
+//!
+//! This allows running the algorithms in petgraph directly on a [HalfEdgeGraph],
+//! vertices are used as nodes ([VertexHandle]) and edges as edges ([EdgeHandle]).
+//! The graph is exposed as undirected, faces and half edges are not visible to petgraph.
+use std::collections::HashSet;
+
+use ::petgraph::graph::IndexType;
+use ::petgraph::visit;
+use ::petgraph::{EdgeType, Graph, Undirected};
+use slotmap::Key;
+
+use crate::{
+    Data, Edge, EdgeHandle, HalfEdgeGraph, Vertex, VertexEdges, VertexFn, VertexHandle,
+    VertexVertex,
+};
+
+/// The slot of the key in its [slotmap::SlotMap]
+///
+/// slotmap doesn't expose the slot, and the layout of [slotmap::KeyData::as_ffi] isn't documented.
+/// It currently has the slot in the lower 32 bits and the version in the upper, which
+/// the `petgraph_algorithms` test checks, so a slotmap release that changes it is caught.
+pub(crate) fn key_index<K: Key>(key: K) -> usize {
+    (key.data().as_ffi() & 0xffff_ffff) as usize
+}
+
+/// Reference to an edge, as seen from its `source` vertex
+pub struct EdgeReference<'graph, DataTypes: Data> {
+    source: VertexHandle,
+    target: VertexHandle,
+    edge: EdgeHandle,
+    weight: &'graph DataTypes::Edge,
+}
+
+impl<'graph, DataTypes: Data> Clone for EdgeReference<'graph, DataTypes> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'graph, DataTypes: Data> Copy for EdgeReference<'graph, DataTypes> {}
+
+impl<'graph, DataTypes: Data> visit::EdgeRef for EdgeReference<'graph, DataTypes> {
+    type NodeId = VertexHandle;
+    type EdgeId = EdgeHandle;
+    type Weight = DataTypes::Edge;
+
+    fn source(&self) -> Self::NodeId {
+        self.source
+    }
+
+    fn target(&self) -> Self::NodeId {
+        self.target
+    }
+
+    fn weight(&self) -> &Self::Weight {
+        self.weight
+    }
+
+    fn id(&self) -> Self::EdgeId {
+        self.edge
+    }
+}
+
+/// Iterator over the neighbors of a vertex, see [visit::IntoNeighbors]
+pub struct VertexNeighbors<'graph, DataTypes: Data> {
+    iter: VertexVertex<'graph, DataTypes>,
+}
+
+impl<'graph, DataTypes: Data> Iterator for VertexNeighbors<'graph, DataTypes> {
+    type Item = VertexHandle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|vertex| vertex.handle())
+    }
+}
+
+/// Iterator over the edges of a vertex, see [visit::IntoEdges]
+pub struct VertexEdgeReferences<'graph, DataTypes: Data> {
+    source: VertexHandle,
+    iter: VertexEdges<'graph, DataTypes>,
+}
+
+impl<'graph, DataTypes: Data> Iterator for VertexEdgeReferences<'graph, DataTypes> {
+    type Item = EdgeReference<'graph, DataTypes>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let edge = self.iter.next()?;
+        let [v1, v2] = edge.vertices();
+        let target = if v1 == self.source { v2 } else { v1 };
+
+        Some(EdgeReference {
+            source: self.source,
+            target: target.handle(),
+            edge: edge.handle(),
+            weight: &edge.graph.edges[edge.handle].data,
+        })
+    }
+}
+
+/// Iterator over all edges in the graph, see [visit::IntoEdgeReferences]
+pub struct EdgeReferences<'graph, DataTypes: Data> {
+    graph: &'graph HalfEdgeGraph<DataTypes>,
+    iter: slotmap::basic::Iter<'graph, EdgeHandle, Edge<DataTypes::Edge>>,
+}
+
+impl<'graph, DataTypes: Data> Iterator for EdgeReferences<'graph, DataTypes> {
+    type Item = EdgeReference<'graph, DataTypes>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (handle, edge) = self.iter.next()?;
+        let hedge = &self.graph.half_edges[edge.hedge];
+        let pair = &self.graph.half_edges[hedge.pair];
+
+        Some(EdgeReference {
+            source: pair.vertex,
+            target: hedge.vertex,
+            edge: handle,
+            weight: &edge.data,
+        })
+    }
+}
+
+/// Iterator over all vertex handles in the graph, see [visit::IntoNodeIdentifiers]
+pub struct VertexIdentifiers<'graph, DataTypes: Data> {
+    iter: slotmap::basic::Keys<'graph, VertexHandle, Vertex<DataTypes::Vertex>>,
+}
+
+impl<'graph, DataTypes: Data> Iterator for VertexIdentifiers<'graph, DataTypes> {
+    type Item = VertexHandle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+/// Iterator over all vertices and their data, see [visit::IntoNodeReferences]
+pub struct VertexReferences<'graph, DataTypes: Data> {
+    iter: slotmap::basic::Iter<'graph, VertexHandle, Vertex<DataTypes::Vertex>>,
+}
+
+impl<'graph, DataTypes: Data> Iterator for VertexReferences<'graph, DataTypes> {
+    type Item = (VertexHandle, &'graph DataTypes::Vertex);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|(handle, vertex)| (handle, &vertex.data))
+    }
+}
+
+impl<DataTypes: Data> visit::GraphBase for HalfEdgeGraph<DataTypes> {
+    type EdgeId = EdgeHandle;
+    type NodeId = VertexHandle;
+}
+
+impl<DataTypes: Data> visit::Data for HalfEdgeGraph<DataTypes> {
+    type NodeWeight = DataTypes::Vertex;
+    type EdgeWeight = DataTypes::Edge;
+}
+
+impl<DataTypes: Data> visit::GraphProp for HalfEdgeGraph<DataTypes> {
+    type EdgeType = Undirected;
+}
+
+impl<DataTypes: Data> visit::NodeCount for HalfEdgeGraph<DataTypes> {
+    fn node_count(&self) -> usize {
+        self.vertices.len()
+    }
+}
+
+impl<DataTypes: Data> visit::EdgeCount for HalfEdgeGraph<DataTypes> {
+    fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+}
+
+/// The index of a vertex is its slot in the underlying [slotmap::SlotMap], so indices are stable
+/// but not compact.
+///
+/// Note that [visit::NodeIndexable::from_index] is `O(n)`, since slotmap doesn't provide a way to
+/// lookup a key from its slot. Algorithms that call it for every vertex are `O(n²)`.
+impl<DataTypes: Data> visit::NodeIndexable for HalfEdgeGraph<DataTypes> {
+    fn node_bound(&self) -> usize {
+        // One slot is reserved by slotmap, so the largest index is capacity
+        self.vertices.capacity() + 1
+    }
+
+    fn to_index(&self, a: Self::NodeId) -> usize {
+        key_index(a)
+    }
+
+    /// `O(n)`, searches all vertices for the one in slot `i`
+    fn from_index(&self, i: usize) -> Self::NodeId {
+        self.vertices
+            .keys()
+            .find(|handle| key_index(*handle) == i)
+            .unwrap_or_else(VertexHandle::null)
+    }
+}
+
+/// The index of a edge is its slot, like for [visit::NodeIndexable].
+///
+/// Note that [visit::EdgeIndexable::from_index] is `O(m)` for `m` edges, algorithms that call it
+/// for every edge are `O(m²)`.
+impl<DataTypes: Data> visit::EdgeIndexable for HalfEdgeGraph<DataTypes> {
+    fn edge_bound(&self) -> usize {
+        self.edges.capacity() + 1
+    }
+
+    fn to_index(&self, a: Self::EdgeId) -> usize {
+        key_index(a)
+    }
+
+    /// `O(m)`, searches all edges for the one in slot `i`
+    fn from_index(&self, i: usize) -> Self::EdgeId {
+        self.edges
+            .keys()
+            .find(|handle| key_index(*handle) == i)
+            .unwrap_or_else(EdgeHandle::null)
+    }
+}
+
+impl<DataTypes: Data> visit::Visitable for HalfEdgeGraph<DataTypes> {
+    type Map = HashSet<VertexHandle>;
+
+    fn visit_map(&self) -> Self::Map {
+        HashSet::with_capacity(self.vertices.len())
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        map.clear();
+    }
+}
+
+impl<'graph, DataTypes: Data> visit::IntoNeighbors for &'graph HalfEdgeGraph<DataTypes> {
+    type Neighbors = VertexNeighbors<'graph, DataTypes>;
+
+    fn neighbors(self, a: Self::NodeId) -> Self::Neighbors {
+        VertexNeighbors {
+            iter: VertexVertex::new(VertexFn::new(self, a)),
+        }
+    }
+}
+
+/// The graph is undirected, so the direction is ignored
+impl<'graph, DataTypes: Data> visit::IntoNeighborsDirected for &'graph HalfEdgeGraph<DataTypes> {
+    type NeighborsDirected = VertexNeighbors<'graph, DataTypes>;
+
+    fn neighbors_directed(
+        self,
+        a: Self::NodeId,
+        _dir: ::petgraph::Direction,
+    ) -> Self::NeighborsDirected {
+        visit::IntoNeighbors::neighbors(self, a)
+    }
+}
+
+impl<'graph, DataTypes: Data> visit::IntoEdges for &'graph HalfEdgeGraph<DataTypes> {
+    type Edges = VertexEdgeReferences<'graph, DataTypes>;
+
+    fn edges(self, a: Self::NodeId) -> Self::Edges {
+        VertexEdgeReferences {
+            source: a,
+            iter: VertexEdges::new(VertexFn::new(self, a)),
+        }
+    }
+}
+
+impl<'graph, DataTypes: Data> visit::IntoEdgeReferences for &'graph HalfEdgeGraph<DataTypes> {
+    type EdgeRef = EdgeReference<'graph, DataTypes>;
+    type EdgeReferences = EdgeReferences<'graph, DataTypes>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        EdgeReferences {
+            graph: self,
+            iter: self.edges.iter(),
+        }
+    }
+}
+
+impl<'graph, DataTypes: Data> visit::IntoNodeIdentifiers for &'graph HalfEdgeGraph<DataTypes> {
+    type NodeIdentifiers = VertexIdentifiers<'graph, DataTypes>;
+
+    fn node_identifiers(self) -> Self::NodeIdentifiers {
+        VertexIdentifiers {
+            iter: self.vertices.keys(),
+        }
+    }
+}
+
+impl<'graph, DataTypes: Data> visit::IntoNodeReferences for &'graph HalfEdgeGraph<DataTypes> {
+    type NodeRef = (VertexHandle, &'graph DataTypes::Vertex);
+    type NodeReferences = VertexReferences<'graph, DataTypes>;
+
+    fn node_references(self) -> Self::NodeReferences {
+        VertexReferences {
+            iter: self.vertices.iter(),
+        }
+    }
+}
+
+/// Creates a graph with vertices as nodes, and edges as edges.
+///
+/// Nodes are added in the same order as [HalfEdgeGraph::iter_vertices] visits the vertices.
+impl<DataTypes: Data> From<&HalfEdgeGraph<DataTypes>>
+    for Graph<DataTypes::Vertex, DataTypes::Edge, Undirected>
+where
+    DataTypes::Vertex: Clone,
+    DataTypes::Edge: Clone,
+{
+    fn from(graph: &HalfEdgeGraph<DataTypes>) -> Self {
+        let mut result = Graph::with_capacity(graph.vertices.len(), graph.edges.len());
+
+        let mut nodes = slotmap::SecondaryMap::with_capacity(graph.vertices.len());
+        for (handle, vertex) in &graph.vertices {
+            nodes.insert(handle, result.add_node(vertex.data.clone()));
+        }
+
+        for edge in graph.iter_edges() {
+            let [v1, v2] = edge.vertices();
+            result.add_edge(nodes[v1.handle()], nodes[v2.handle()], (*edge).clone());
+        }
+
+        result
+    }
+}
+
+/// Creates a graph with a vertex for each node, and a edge for each edge.
+///
+/// Edges that can't be represented (self loops & parallel edges) are skipped.
+impl<DataTypes: Data + Default, Ty: EdgeType, Ix: IndexType>
+    From<Graph<DataTypes::Vertex, DataTypes::Edge, Ty, Ix>> for HalfEdgeGraph<DataTypes>
+{
+    fn from(graph: Graph<DataTypes::Vertex, DataTypes::Edge, Ty, Ix>) -> Self {
+        let mut result = HalfEdgeGraph::default();
+
+        let (nodes, edges) = graph.into_nodes_edges();
+        let vertices: Vec<_> = nodes
+            .into_iter()
+            .map(|node| result.new_vertex(node.weight))
+            .collect();
+
+        for edge in edges {
+            let v1 = vertices[edge.source().index()];
+            let v2 = vertices[edge.target().index()];
+            result.new_edge(v1, v2, edge.weight);
+        }
+
+        result
+    }
+}
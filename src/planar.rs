@@ -0,0 +1,213 @@
+//! Construction of planar graphs, where the order of the edges around each vertex
+//! is decided by the position of the vertices.
+//!
+//! For the faces to be consistent, the out half edges around a vertex is ordered clockwise,
+//! which makes the faces counter clockwise.
+use crate::{
+    Data, EdgeHandle, Face, FaceHandle, HalfEdgeGraph, HalfEdgeHandle, VertexFn, VertexHandle,
+};
+
+fn is_finite(position: [f64; 2]) -> bool {
+    position.iter().all(|c| c.is_finite())
+}
+
+fn angle_to(from: [f64; 2], to: [f64; 2]) -> f64 {
+    (to[1] - from[1]).atan2(to[0] - from[0])
+}
+
+impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
+    /// Creates a new edge like [HalfEdgeGraph::new_edge], but the new half edges are inserted
+    /// at the correct angle around `v1` & `v2`, given by `positions`.
+    ///
+    /// Fails if the edge would be placed inside a face, or if any of the positions used
+    /// aren't finite.
+    ///
+    /// Unlike [HalfEdgeGraph::new_edge] this never creates self loops or parallel edges, even if
+    /// [Data::ALLOW_SELF_LOOPS] or [Data::ALLOW_PARALLEL_EDGES] is set. A straight self loop has
+    /// no angle, and a parallel edge has the same angle as the existing edge, so their place
+    /// around the vertices is ambiguous.
+    ///
+    /// The positions are trusted to be a straight-line planar drawing, it isn't checked that the
+    /// new edge doesn't cross other edges.
+    pub fn new_edge_embedded<F>(
+        &mut self,
+        v1: VertexHandle,
+        v2: VertexHandle,
+        data: DataTypes::Edge,
+        positions: F,
+    ) -> Option<EdgeHandle>
+    where
+        F: Fn(VertexFn<'_, DataTypes>) -> [f64; 2],
+    {
+        if v1 == v2 {
+            return None;
+        }
+        if self.find_edge(v1, v2).is_some() {
+            // A parallel edge would overlap the existing edge
+            return None;
+        }
+
+        let p1 = positions(self.vertex(v1)?);
+        let p2 = positions(self.vertex(v2)?);
+        if !is_finite(p1) || !is_finite(p2) {
+            return None;
+        }
+
+        let v1_insertion = self.find_angular_insertion(v1, angle_to(p1, p2), &positions)?;
+        let v2_insertion = self.find_angular_insertion(v2, angle_to(p2, p1), &positions)?;
+
+        Some(self.insert_edge(v1, v2, data, v1_insertion, v2_insertion))
+    }
+
+    /// Finds the out half edge, that a new out half edge with `angle` should be inserted before.
+    ///
+    /// Returns `Some(None)` if the vertex doesn't have any edges,
+    /// and `None` if the insertion point is inside a face or a neighbor position isn't finite.
+    fn find_angular_insertion<F>(
+        &self,
+        vertex: VertexHandle,
+        angle: f64,
+        positions: &F,
+    ) -> Option<Option<HalfEdgeHandle>>
+    where
+        F: Fn(VertexFn<'_, DataTypes>) -> [f64; 2],
+    {
+        let vertex = self.vertex(vertex)?;
+        let position = positions(VertexFn::new(self, vertex.handle()));
+
+        let mut deltas = Vec::new();
+        for hedge in vertex.out_half_edges() {
+            let neighbor = positions(hedge.vertex());
+            if !is_finite(neighbor) {
+                return None;
+            }
+            let delta = angle - angle_to(position, neighbor);
+            deltas.push((delta.rem_euclid(2. * std::f64::consts::PI), hedge));
+        }
+
+        let insertion = deltas
+            .into_iter()
+            // The closest half edge clockwise, a edge with the same angle is placed last
+            .min_by(|(a, _), (b, _)| {
+                let a = if *a == 0. { f64::INFINITY } else { *a };
+                let b = if *b == 0. { f64::INFINITY } else { *b };
+                a.total_cmp(&b)
+            })
+            .map(|(_, hedge)| hedge);
+
+        match insertion {
+            Some(hedge) if hedge.face().is_some() => None,
+            Some(hedge) => Some(Some(hedge.handle())),
+            None => Some(None),
+        }
+    }
+
+    /// Sorts the half edges around every vertex by their angle and creates all faces,
+    /// including the outer face of each connected component (which is clockwise).
+    ///
+    /// The graph must not contain any faces, and all positions must be finite.
+    /// Returns the new faces.
+    ///
+    /// The positions are trusted to be a straight-line planar drawing. Crossing edges aren't
+    /// detected, and gives faces that doesn't match the drawing.
+    pub fn embed_planar<F>(&mut self, positions: F) -> Option<Vec<FaceHandle>>
+    where
+        F: Fn(VertexFn<'_, DataTypes>) -> [f64; 2],
+    {
+        if !self.faces.is_empty() {
+            return None;
+        }
+        if !self
+            .iter_vertices()
+            .all(|vertex| is_finite(positions(vertex)))
+        {
+            return None;
+        }
+
+        let rotations: Vec<(VertexHandle, Vec<HalfEdgeHandle>)> = self
+            .iter_vertices()
+            .map(|vertex| {
+                let position = positions(VertexFn::new(self, vertex.handle()));
+                let mut hedges: Vec<_> = vertex
+                    .out_half_edges()
+                    .map(|hedge| {
+                        (
+                            angle_to(position, positions(hedge.vertex())),
+                            hedge.handle(),
+                        )
+                    })
+                    .collect();
+                // clockwise
+                hedges.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+                (
+                    vertex.handle(),
+                    hedges.into_iter().map(|(_, hedge)| hedge).collect(),
+                )
+            })
+            .collect();
+
+        for (vertex, hedges) in rotations {
+            self.set_rotation(vertex, &hedges);
+        }
+
+        Some(self.fill_faces())
+    }
+
+    /// Relinks the fan around `vertex`, so the out half edges comes in the same order as `hedges`.
+    ///
+    /// `hedges` must contain all out half edges of vertex, and non of them may be part of a face.
+    pub(crate) fn set_rotation(&mut self, vertex: VertexHandle, hedges: &[HalfEdgeHandle]) {
+        debug_assert_eq!(
+            self.vertex(vertex).unwrap().out_half_edges().count(),
+            hedges.len()
+        );
+
+        for (i, &hedge) in hedges.iter().enumerate() {
+            let next = hedges[(i + 1) % hedges.len()];
+            let pair = self.half_edges[hedge].pair;
+
+            debug_assert_eq!(self.half_edges[pair].vertex, vertex);
+
+            self.link_hedges(pair, next);
+        }
+    }
+
+    /// Creates a face for every loop of half edges that isn't part of a face
+    pub(crate) fn fill_faces(&mut self) -> Vec<FaceHandle> {
+        let free: Vec<_> = self
+            .half_edges
+            .iter()
+            .filter(|(_, hedge)| hedge.face.is_null())
+            .map(|(handle, _)| handle)
+            .collect();
+
+        let mut faces = Vec::new();
+        for hedge in free {
+            if !self.half_edges[hedge].face.is_null() {
+                continue;
+            }
+
+            let face = self.faces.insert(Face {
+                data: Default::default(),
+                hedge,
+            });
+            self.record_new(face);
+
+            let mut current = hedge;
+            loop {
+                self.set_hedge_face(current, face);
+                current = self.half_edges[current].next;
+                if current == hedge {
+                    break;
+                }
+            }
+            faces.push(face);
+        }
+
+        #[cfg(test)]
+        self.verify_invarians();
+
+        faces
+    }
+}
@@ -0,0 +1,688 @@
+//! Planarity testing and combinatorial embedding of graphs without positions.
+//!
+//! Uses the Left-Right planarity test by de Fraysseix & Rosenstiehl, as described by
+//! Ulrik Brandes in "The Left-Right Planarity Test".
+use std::collections::{HashMap, HashSet};
+
+use crate::{Data, EdgeHandle, FaceHandle, HalfEdgeGraph, VertexHandle};
+
+/// Reasons why [HalfEdgeGraph::planar_embedding] can fail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmbeddingError {
+    /// The graph already have faces, and can't be re-embedded
+    HasFaces,
+    /// The graph isn't planar, contains the edges of a subgraph that is a subdivision
+    /// of K5 or K3,3 (a Kuratowski subgraph).
+    NonPlanar(Vec<EdgeHandle>),
+    /// The graph have parallel edges or self loops, see [Data::ALLOW_PARALLEL_EDGES]
+    NotSimple,
+}
+
+impl std::fmt::Display for EmbeddingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmbeddingError::HasFaces => write!(f, "graph already have faces"),
+            EmbeddingError::NonPlanar(edges) => write!(
+                f,
+                "graph isn't planar, found a kuratowski subgraph with {} edges",
+                edges.len()
+            ),
+            EmbeddingError::NotSimple => write!(f, "graph have parallel edges or self loops"),
+        }
+    }
+}
+
+impl std::error::Error for EmbeddingError {}
+
+const UNVISITED: usize = usize::MAX;
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Interval {
+    low: Option<usize>,
+    high: Option<usize>,
+}
+
+impl Interval {
+    fn is_empty(&self) -> bool {
+        self.low.is_none() && self.high.is_none()
+    }
+}
+
+#[derive(Debug)]
+struct ConflictPair {
+    // used to identify the pair on the stack
+    id: usize,
+    left: Interval,
+    right: Interval,
+}
+
+impl ConflictPair {
+    fn swap(&mut self) {
+        std::mem::swap(&mut self.left, &mut self.right);
+    }
+}
+
+/// Cyclic clockwise order of neighbors around each vertex
+struct Rotation {
+    first: Vec<Option<usize>>,
+    // (start, end) -> (clockwise, counter clockwise)
+    links: HashMap<(usize, usize), (usize, usize)>,
+}
+
+impl Rotation {
+    fn add_cw(&mut self, start: usize, end: usize, reference: Option<usize>) {
+        match reference {
+            None => {
+                debug_assert!(self.first[start].is_none());
+                self.links.insert((start, end), (end, end));
+                self.first[start] = Some(end);
+            }
+            Some(reference) => {
+                let cw = self.links[&(start, reference)].0;
+                self.links.get_mut(&(start, reference)).unwrap().0 = end;
+                self.links.insert((start, end), (cw, reference));
+                self.links.get_mut(&(start, cw)).unwrap().1 = end;
+            }
+        }
+    }
+
+    fn add_ccw(&mut self, start: usize, end: usize, reference: Option<usize>) {
+        match reference {
+            None => self.add_cw(start, end, None),
+            Some(reference) => {
+                let ccw = self.links[&(start, reference)].1;
+                self.add_cw(start, end, Some(ccw));
+                if self.first[start] == Some(reference) {
+                    self.first[start] = Some(end);
+                }
+            }
+        }
+    }
+
+    fn add_first(&mut self, start: usize, end: usize) {
+        let reference = self.first[start];
+        self.add_ccw(start, end, reference);
+    }
+
+    fn neighbors(&self, vertex: usize) -> Vec<usize> {
+        let mut result = Vec::new();
+        if let Some(first) = self.first[vertex] {
+            let mut current = first;
+            loop {
+                result.push(current);
+                current = self.links[&(vertex, current)].0;
+                if current == first {
+                    break;
+                }
+            }
+        }
+        result
+    }
+}
+
+/// The state of the planarity test, works on a simple graph with vertices `0..n`
+struct LrPlanarity {
+    adjacency: Vec<Vec<(usize, usize)>>,
+
+    // edges are oriented durring the first dfs
+    oriented: Vec<bool>,
+    source: Vec<usize>,
+    target: Vec<usize>,
+    out_edges: Vec<Vec<usize>>,
+
+    roots: Vec<usize>,
+    height: Vec<usize>,
+    parent_edge: Vec<Option<usize>>,
+    lowpt: Vec<usize>,
+    lowpt2: Vec<usize>,
+    nesting_depth: Vec<isize>,
+
+    reference: Vec<Option<usize>>,
+    side: Vec<isize>,
+    stack: Vec<ConflictPair>,
+    next_id: usize,
+    stack_bottom: Vec<Option<usize>>,
+    lowpt_edge: Vec<Option<usize>>,
+
+    left_ref: Vec<usize>,
+    right_ref: Vec<usize>,
+}
+
+impl LrPlanarity {
+    fn new(vertex_count: usize, edges: &[(usize, usize)]) -> Self {
+        let mut adjacency = vec![Vec::new(); vertex_count];
+        for (edge, &(v1, v2)) in edges.iter().enumerate() {
+            adjacency[v1].push((v2, edge));
+            adjacency[v2].push((v1, edge));
+        }
+
+        let m = edges.len();
+        Self {
+            adjacency,
+
+            oriented: vec![false; m],
+            source: vec![0; m],
+            target: vec![0; m],
+            out_edges: vec![Vec::new(); vertex_count],
+
+            roots: Vec::new(),
+            height: vec![UNVISITED; vertex_count],
+            parent_edge: vec![None; vertex_count],
+            lowpt: vec![0; m],
+            lowpt2: vec![0; m],
+            nesting_depth: vec![0; m],
+
+            reference: vec![None; m],
+            side: vec![1; m],
+            stack: Vec::new(),
+            next_id: 0,
+            stack_bottom: vec![None; m],
+            lowpt_edge: vec![None; m],
+
+            left_ref: vec![0; vertex_count],
+            right_ref: vec![0; vertex_count],
+        }
+    }
+
+    /// Returns true if the graph is planar
+    fn test(&mut self) -> bool {
+        let n = self.height.len();
+        let m = self.oriented.len();
+        if n > 2 && m > 3 * n - 6 {
+            return false;
+        }
+
+        for v in 0..n {
+            if self.height[v] == UNVISITED {
+                self.height[v] = 0;
+                self.roots.push(v);
+                self.dfs_orientation(v);
+            }
+        }
+
+        self.sort_out_edges();
+        for root in self.roots.clone() {
+            if !self.dfs_testing(root) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Calculates the rotation system, must only be called after [LrPlanarity::test] returned true
+    fn embed(mut self) -> Rotation {
+        for edge in 0..self.oriented.len() {
+            self.nesting_depth[edge] *= self.sign(edge);
+        }
+        self.sort_out_edges();
+
+        let n = self.height.len();
+        let mut rotation = Rotation {
+            first: vec![None; n],
+            links: HashMap::new(),
+        };
+
+        for v in 0..n {
+            let mut previous = None;
+            for &edge in &self.out_edges[v] {
+                let w = self.target[edge];
+                rotation.add_cw(v, w, previous);
+                previous = Some(w);
+            }
+        }
+
+        for root in self.roots.clone() {
+            self.dfs_embedding(root, &mut rotation);
+        }
+
+        rotation
+    }
+
+    fn sort_out_edges(&mut self) {
+        let nesting_depth = &self.nesting_depth;
+        for edges in &mut self.out_edges {
+            edges.sort_by_key(|edge| nesting_depth[*edge]);
+        }
+    }
+
+    /// Orients the edges of the component of `root` in dfs order, and calculates their lowpoints.
+    ///
+    /// The dfs is iterative, so long paths doesn't overflow the call stack.
+    fn dfs_orientation(&mut self, root: usize) {
+        // vertex and the index of its next adjacent edge
+        let mut stack = vec![(root, 0)];
+
+        while let Some(&(v, i)) = stack.last() {
+            if i == self.adjacency[v].len() {
+                stack.pop();
+                if let Some(e) = self.parent_edge[v] {
+                    // returning along the tree edge
+                    self.finish_orientation(e);
+                }
+                continue;
+            }
+            stack.last_mut().unwrap().1 += 1;
+
+            let (w, edge) = self.adjacency[v][i];
+            if self.oriented[edge] {
+                continue;
+            }
+            self.oriented[edge] = true;
+            self.source[edge] = v;
+            self.target[edge] = w;
+            self.out_edges[v].push(edge);
+
+            self.lowpt[edge] = self.height[v];
+            self.lowpt2[edge] = self.height[v];
+
+            if self.height[w] == UNVISITED {
+                // tree edge
+                self.parent_edge[w] = Some(edge);
+                self.height[w] = self.height[v] + 1;
+                stack.push((w, 0));
+            } else {
+                // back edge
+                self.lowpt[edge] = self.height[w];
+                self.finish_orientation(edge);
+            }
+        }
+    }
+
+    /// Calculates the nesting depth of `edge` once its lowpoints are known,
+    /// and passes them on to the parent edge.
+    fn finish_orientation(&mut self, edge: usize) {
+        let v = self.source[edge];
+
+        // determine nesting graph
+        self.nesting_depth[edge] = 2 * self.lowpt[edge] as isize;
+        if self.lowpt2[edge] < self.height[v] {
+            // chordal
+            self.nesting_depth[edge] += 1;
+        }
+
+        // update lowpoints of parent edge
+        if let Some(e) = self.parent_edge[v] {
+            if self.lowpt[edge] < self.lowpt[e] {
+                self.lowpt2[e] = self.lowpt[e].min(self.lowpt2[edge]);
+                self.lowpt[e] = self.lowpt[edge];
+            } else if self.lowpt[edge] > self.lowpt[e] {
+                self.lowpt2[e] = self.lowpt2[e].min(self.lowpt[edge]);
+            } else {
+                self.lowpt2[e] = self.lowpt2[e].min(self.lowpt2[edge]);
+            }
+        }
+    }
+
+    fn top_id(&self) -> Option<usize> {
+        self.stack.last().map(|pair| pair.id)
+    }
+
+    fn new_pair(&mut self, left: Interval, right: Interval) -> ConflictPair {
+        self.next_id += 1;
+        ConflictPair {
+            id: self.next_id,
+            left,
+            right,
+        }
+    }
+
+    fn conflicting(&self, interval: Interval, edge: usize) -> bool {
+        match interval.high {
+            Some(high) => self.lowpt[high] > self.lowpt[edge],
+            None => false,
+        }
+    }
+
+    fn lowest(&self, pair: &ConflictPair) -> usize {
+        match (pair.left.low, pair.right.low) {
+            (None, Some(right)) => self.lowpt[right],
+            (Some(left), None) => self.lowpt[left],
+            (Some(left), Some(right)) => self.lowpt[left].min(self.lowpt[right]),
+            (None, None) => unreachable!("Empty conflict pair on stack"),
+        }
+    }
+
+    /// Builds the conflict pairs of the component of `root`, returns false if they can't be
+    /// resolved, and the graph isn't planar.
+    ///
+    /// The dfs is iterative, so long paths doesn't overflow the call stack.
+    fn dfs_testing(&mut self, root: usize) -> bool {
+        // vertex and the index of its next out edge
+        let mut stack = vec![(root, 0)];
+
+        while let Some(&(v, i)) = stack.last() {
+            if i == self.out_edges[v].len() {
+                stack.pop();
+                if let Some(e) = self.parent_edge[v] {
+                    // remove back edges returning to parent
+                    self.remove_back_edges(e);
+                    if !self.integrate_return_edges(e) {
+                        return false;
+                    }
+                }
+                continue;
+            }
+            stack.last_mut().unwrap().1 += 1;
+
+            let edge = self.out_edges[v][i];
+            let w = self.target[edge];
+
+            self.stack_bottom[edge] = self.top_id();
+            if self.parent_edge[w] == Some(edge) {
+                // tree edge
+                stack.push((w, 0));
+            } else {
+                // back edge
+                self.lowpt_edge[edge] = Some(edge);
+                let pair = self.new_pair(
+                    Interval::default(),
+                    Interval {
+                        low: Some(edge),
+                        high: Some(edge),
+                    },
+                );
+                self.stack.push(pair);
+                if !self.integrate_return_edges(edge) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Adds the return edges of a finished out edge to the constraints of its source
+    fn integrate_return_edges(&mut self, edge: usize) -> bool {
+        let v = self.source[edge];
+        if self.lowpt[edge] < self.height[v] {
+            let e = self.parent_edge[v].expect("Only the root can be without parent");
+            if self.out_edges[v][0] == edge {
+                self.lowpt_edge[e] = self.lowpt_edge[edge];
+            } else if !self.add_constraints(edge, e) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn add_constraints(&mut self, ei: usize, e: usize) -> bool {
+        let mut p = self.new_pair(Interval::default(), Interval::default());
+
+        // merge return edges of ei into p.right
+        loop {
+            let mut q = self.stack.pop().unwrap();
+            if !q.left.is_empty() {
+                q.swap();
+            }
+            if !q.left.is_empty() {
+                return false;
+            }
+
+            let q_low = q.right.low.unwrap();
+            if self.lowpt[q_low] > self.lowpt[e] {
+                // merge intervals
+                if p.right.is_empty() {
+                    p.right = q.right;
+                } else if let Some(low) = p.right.low {
+                    self.reference[low] = q.right.high;
+                }
+                p.right.low = q.right.low;
+            } else {
+                // align
+                self.reference[q_low] = self.lowpt_edge[e];
+            }
+
+            if self.top_id() == self.stack_bottom[ei] {
+                break;
+            }
+        }
+
+        // merge conflicting return edges of e1, ..., ei-1 into p.left
+        while let Some(top) = self.stack.last() {
+            if !self.conflicting(top.left, ei) && !self.conflicting(top.right, ei) {
+                break;
+            }
+
+            let mut q = self.stack.pop().unwrap();
+            if self.conflicting(q.right, ei) {
+                q.swap();
+            }
+            if self.conflicting(q.right, ei) {
+                return false;
+            }
+
+            // merge interval below lowpt(ei) into p.right
+            if let Some(low) = p.right.low {
+                self.reference[low] = q.right.high;
+            }
+            if q.right.low.is_some() {
+                p.right.low = q.right.low;
+            }
+
+            if p.left.is_empty() {
+                p.left = q.left;
+            } else if let Some(low) = p.left.low {
+                self.reference[low] = q.left.high;
+            }
+            p.left.low = q.left.low;
+        }
+
+        if !(p.left.is_empty() && p.right.is_empty()) {
+            self.stack.push(p);
+        }
+        true
+    }
+
+    fn remove_back_edges(&mut self, e: usize) {
+        let u = self.source[e];
+
+        // trim back edges ending at parent u, drop entire conflict pairs
+        while let Some(top) = self.stack.last() {
+            if self.lowest(top) != self.height[u] {
+                break;
+            }
+            let p = self.stack.pop().unwrap();
+            if let Some(low) = p.left.low {
+                self.side[low] = -1;
+            }
+        }
+
+        // one more conflict pair to consider
+        if let Some(mut p) = self.stack.pop() {
+            // trim left interval
+            while let Some(high) = p.left.high {
+                if self.target[high] != u {
+                    break;
+                }
+                p.left.high = self.reference[high];
+            }
+            if p.left.high.is_none() {
+                if let Some(low) = p.left.low {
+                    // just emptied
+                    self.reference[low] = p.right.low;
+                    self.side[low] = -1;
+                    p.left.low = None;
+                }
+            }
+
+            // trim right interval
+            while let Some(high) = p.right.high {
+                if self.target[high] != u {
+                    break;
+                }
+                p.right.high = self.reference[high];
+            }
+            if p.right.high.is_none() {
+                if let Some(low) = p.right.low {
+                    // just emptied
+                    self.reference[low] = p.left.low;
+                    self.side[low] = -1;
+                    p.right.low = None;
+                }
+            }
+
+            self.stack.push(p);
+        }
+
+        // side of e is side of a highest return edge
+        if self.lowpt[e] < self.height[u] {
+            let top = self.stack.last().unwrap();
+            let (hl, hr) = (top.left.high, top.right.high);
+
+            self.reference[e] = match (hl, hr) {
+                (Some(hl), Some(hr)) if self.lowpt[hl] > self.lowpt[hr] => Some(hl),
+                (Some(hl), None) => Some(hl),
+                _ => hr,
+            };
+        }
+    }
+
+    fn sign(&mut self, edge: usize) -> isize {
+        // the chain of references can be long, resolve it from the end
+        let mut chain = vec![edge];
+        while let Some(reference) = self.reference[*chain.last().unwrap()] {
+            chain.push(reference);
+        }
+        for i in (1..chain.len()).rev() {
+            self.side[chain[i - 1]] *= self.side[chain[i]];
+            self.reference[chain[i - 1]] = None;
+        }
+        self.side[edge]
+    }
+
+    /// Adds the edges of the component of `root` to the rotation system, on their side.
+    ///
+    /// The dfs is iterative, so long paths doesn't overflow the call stack.
+    fn dfs_embedding(&mut self, root: usize, rotation: &mut Rotation) {
+        // vertex and the index of its next out edge
+        let mut stack = vec![(root, 0)];
+
+        while let Some(&(v, i)) = stack.last() {
+            if i == self.out_edges[v].len() {
+                stack.pop();
+                continue;
+            }
+            stack.last_mut().unwrap().1 += 1;
+
+            let edge = self.out_edges[v][i];
+            let w = self.target[edge];
+
+            if self.parent_edge[w] == Some(edge) {
+                // tree edge
+                rotation.add_first(w, v);
+                self.left_ref[v] = w;
+                self.right_ref[v] = w;
+                stack.push((w, 0));
+            } else if self.side[edge] == 1 {
+                // back edge, on the right side
+                rotation.add_cw(w, v, Some(self.right_ref[w]));
+            } else {
+                // back edge, on the left side
+                rotation.add_ccw(w, v, Some(self.left_ref[w]));
+                self.left_ref[w] = v;
+            }
+        }
+    }
+}
+
+impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
+    /// Maps the graph to vertex indices, and edges between them
+    ///
+    /// Self loops and parallel edges doesn't affect planarity, so they are skipped.
+    fn planarity_input(&self) -> (Vec<VertexHandle>, Vec<EdgeHandle>, Vec<(usize, usize)>) {
+        let vertices: Vec<_> = self.vertices.keys().collect();
+        let indices: HashMap<_, _> = vertices.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+
+        let mut handles = Vec::with_capacity(self.edges.len());
+        let mut edges = Vec::with_capacity(self.edges.len());
+        let mut seen = HashSet::new();
+        for edge in self.iter_edges() {
+            let [v1, v2] = edge.vertices();
+            let (v1, v2) = (indices[&v1.handle()], indices[&v2.handle()]);
+            if v1 == v2 || !seen.insert((v1.min(v2), v1.max(v2))) {
+                continue;
+            }
+
+            handles.push(edge.handle());
+            edges.push((v1, v2));
+        }
+
+        (vertices, handles, edges)
+    }
+
+    /// Test if the graph is planar, ignoring any faces.
+    pub fn is_planar(&self) -> bool {
+        let (vertices, _, edges) = self.planarity_input();
+        LrPlanarity::new(vertices.len(), &edges).test()
+    }
+
+    /// Embeds the graph in the plane without using positions.
+    ///
+    /// If the graph is planar, the half edges around every vertex is reordered to a planar
+    /// rotation system, and all faces (including the outer face of each connected component)
+    /// are created and returned.
+    ///
+    /// If the graph isn't planar, a Kuratowski subgraph is returned as witness. Finding it runs
+    /// the planarity test once per edge, so failing takes `O(m²)` time for `m` edges, while
+    /// succeeding is linear.
+    /// The graph must not contain any faces, parallel edges or self loops.
+    pub fn planar_embedding(&mut self) -> Result<Vec<FaceHandle>, EmbeddingError> {
+        if !self.faces.is_empty() {
+            return Err(EmbeddingError::HasFaces);
+        }
+
+        let (vertices, handles, edges) = self.planarity_input();
+        if edges.len() != self.edges.len() {
+            return Err(EmbeddingError::NotSimple);
+        }
+
+        let mut planarity = LrPlanarity::new(vertices.len(), &edges);
+        if !planarity.test() {
+            return Err(EmbeddingError::NonPlanar(
+                kuratowski_subgraph(vertices.len(), &edges)
+                    .into_iter()
+                    .map(|edge| handles[edge])
+                    .collect(),
+            ));
+        }
+
+        let rotation = planarity.embed();
+        for (i, &vertex) in vertices.iter().enumerate() {
+            let hedges: Vec<_> = rotation
+                .neighbors(i)
+                .into_iter()
+                .map(|neighbor| self.find_half_edge(vertex, vertices[neighbor]).unwrap())
+                .collect();
+
+            if !hedges.is_empty() {
+                self.set_rotation(vertex, &hedges);
+            }
+        }
+
+        Ok(self.fill_faces())
+    }
+}
+
+/// Finds a minimal non planar subgraph, by removing all edges that isn't needed to make it non planar.
+///
+/// Tests the planarity once for every edge, `O(m²)` in total. The first tests of a dense graph
+/// are cheap, they fail on the edge count before any dfs.
+fn kuratowski_subgraph(vertex_count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut remaining: Vec<usize> = (0..edges.len()).collect();
+    let mut i = 0;
+    while i < remaining.len() {
+        let subgraph: Vec<_> = remaining
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, edge)| edges[*edge])
+            .collect();
+
+        if LrPlanarity::new(vertex_count, &subgraph).test() {
+            // the edge is needed
+            i += 1;
+        } else {
+            remaining.remove(i);
+        }
+    }
+    remaining
+}
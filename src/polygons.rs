@@ -0,0 +1,248 @@
+//! Building a graph from a polygon soup, that might not be representable as a half edge graph
+//!
+//! Each half edge have a single pair, so a edge can only be shared by 2 faces with opposite winding.
+//! [HalfEdgeGraph::add_polygons] handles edges shared by more faces (or faces with the same winding)
+//! by giving the extra faces their own copy of the edge, and records which edges are glued together
+//! so the original polygons can be reconstructed.
+//!
+//! [HalfEdgeGraph::add_oriented_polygons] also flips polygons with the opposite winding of their
+//! neighbors before they are added, so they don't need any glue.
+use std::collections::{HashMap, VecDeque};
+
+use itertools::Itertools;
+
+use crate::{Data, EdgeHandle, FaceError, FaceHandle, HalfEdgeGraph, HalfEdgeHandle, VertexHandle};
+
+/// A set of edges between the same vertices, that represents the same edge in the input polygons
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GluedEdge {
+    pub vertices: [VertexHandle; 2],
+    /// The first edge is the original, and the rest are the copies
+    pub edges: Vec<EdgeHandle>,
+}
+
+/// Result of [HalfEdgeGraph::add_polygons]
+#[derive(Debug, Clone, Default)]
+pub struct PolygonImport {
+    /// The face created for each polygon, in the same order as the polygons
+    ///
+    /// A failed polygon can still have added edges for some of its sides. They stay in the graph,
+    /// and copies of shared edges are still reported in [PolygonImport::glued].
+    pub faces: Vec<Result<FaceHandle, FaceError>>,
+    /// The edges that was split, since they are shared by more faces than a half edge graph can represent
+    pub glued: Vec<GluedEdge>,
+    /// The indices of the polygons that was reversed, see [HalfEdgeGraph::add_oriented_polygons]
+    pub flipped: Vec<usize>,
+    /// The indices of the polygons in each component that can't be oriented consistently
+    /// (like a möbius strip), see [HalfEdgeGraph::add_oriented_polygons]
+    pub non_orientable: Vec<Vec<usize>>,
+}
+
+impl PolygonImport {
+    /// Returns true if all polygons was added without any glued edges
+    pub fn is_manifold(&self) -> bool {
+        self.glued.is_empty() && self.faces.iter().all(|face| face.is_ok())
+    }
+}
+
+/// Reverses the polygons that have the opposite winding of their neighbors.
+///
+/// The orientation is propagated breadth first from the first polygon of each component, over
+/// the edges that are shared by exactly 2 polygons. Returns the flipped polygons, and the
+/// components where a polygon would need to be flipped twice.
+fn orient_polygons(polygons: &mut [Vec<VertexHandle>]) -> (Vec<usize>, Vec<Vec<usize>>) {
+    // The polygons using each edge, and if they go from the smaller vertex to the larger
+    let mut sides: HashMap<(VertexHandle, VertexHandle), Vec<(usize, bool)>> = HashMap::new();
+    for (index, polygon) in polygons.iter().enumerate() {
+        for (&v1, &v2) in polygon.iter().circular_tuple_windows() {
+            if v1 != v2 {
+                let key = (v1.min(v2), v1.max(v2));
+                sides.entry(key).or_default().push((index, v1 < v2));
+            }
+        }
+    }
+
+    let mut flip: Vec<Option<bool>> = vec![None; polygons.len()];
+    let mut non_orientable = Vec::new();
+
+    for seed in 0..polygons.len() {
+        if flip[seed].is_some() {
+            continue;
+        }
+        flip[seed] = Some(false);
+
+        let mut component = vec![seed];
+        let mut orientable = true;
+        let mut queue = VecDeque::from(vec![seed]);
+
+        while let Some(index) = queue.pop_front() {
+            let flipped = flip[index].unwrap();
+
+            for (&v1, &v2) in polygons[index].iter().circular_tuple_windows() {
+                let key = (v1.min(v2), v1.max(v2));
+                let (neighbor, forward) = match sides.get(&key).map(Vec::as_slice) {
+                    Some([a, b]) if a.0 == index && b.0 != index => *b,
+                    Some([a, b]) if b.0 == index && a.0 != index => *a,
+                    // Non-manifold edge, or a boundary
+                    _ => continue,
+                };
+
+                // Neighbors must use the edge in opposite directions
+                let wanted = flipped ^ ((v1 < v2) == forward);
+                match flip[neighbor] {
+                    None => {
+                        flip[neighbor] = Some(wanted);
+                        component.push(neighbor);
+                        queue.push_back(neighbor);
+                    }
+                    Some(current) => orientable &= current == wanted,
+                }
+            }
+        }
+
+        if !orientable {
+            component.sort_unstable();
+            non_orientable.push(component);
+        }
+    }
+
+    let flipped: Vec<_> = (0..polygons.len())
+        .filter(|index| flip[*index] == Some(true))
+        .collect();
+    for &index in &flipped {
+        polygons[index].reverse();
+    }
+
+    (flipped, non_orientable)
+}
+
+impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
+    /// Adds a face for each polygon, the faces gets the default data.
+    ///
+    /// Unlike [HalfEdgeGraph::new_face] this doesn't fail when a side of a polygon is already used
+    /// by another face, instead a copy of the edge is created, and reported in [PolygonImport::glued].
+    /// The copies are parallel edges, even if [Data::ALLOW_PARALLEL_EDGES] is false,
+    /// so [HalfEdgeGraph::find_edge] can return either of them.
+    ///
+    /// A polygon can still fail, if a vertex doesn't exist, or if there is no room around
+    /// a vertex to insert a copy of a edge (the vertex is surrounded by faces).
+    /// The edges created for the sides of a failed polygon are not removed, see [PolygonImport::faces].
+    pub fn add_polygons<P>(&mut self, polygons: &[P]) -> PolygonImport
+    where
+        P: AsRef<[VertexHandle]>,
+    {
+        let mut result = PolygonImport::default();
+        let mut glue_index = HashMap::new();
+
+        for polygon in polygons {
+            let face = self.add_polygon(polygon.as_ref(), &mut result.glued, &mut glue_index);
+            result.faces.push(face);
+        }
+
+        result
+    }
+
+    /// Same as [HalfEdgeGraph::add_polygons], but first reverses the polygons that have the opposite
+    /// winding of their neighbors, see [PolygonImport::flipped].
+    ///
+    /// Components that can't be oriented consistently are reported in [PolygonImport::non_orientable],
+    /// the edges where the orientation conflicts are glued.
+    pub fn add_oriented_polygons<P>(&mut self, polygons: &[P]) -> PolygonImport
+    where
+        P: AsRef<[VertexHandle]>,
+    {
+        let mut polygons: Vec<_> = polygons
+            .iter()
+            .map(|polygon| polygon.as_ref().to_vec())
+            .collect();
+        let (flipped, non_orientable) = orient_polygons(&mut polygons);
+
+        PolygonImport {
+            flipped,
+            non_orientable,
+            ..self.add_polygons(&polygons)
+        }
+    }
+
+    fn add_polygon(
+        &mut self,
+        polygon: &[VertexHandle],
+        glued: &mut Vec<GluedEdge>,
+        glue_index: &mut HashMap<(VertexHandle, VertexHandle), usize>,
+    ) -> Result<FaceHandle, FaceError> {
+        if polygon.is_empty() {
+            return Err(FaceError::Empty);
+        }
+        for (&v1, &v2) in polygon.iter().circular_tuple_windows() {
+            if !self.vertices.contains_key(v1) {
+                return Err(FaceError::InvalidVertex(v1));
+            }
+            if v1 == v2 && !DataTypes::ALLOW_SELF_LOOPS {
+                return Err(FaceError::SelfLoop(v1));
+            }
+        }
+
+        let mut hedges = Vec::with_capacity(polygon.len());
+        for (&v1, &v2) in polygon.iter().circular_tuple_windows() {
+            let free = self
+                .find_half_edges(v1, v2)
+                .find(|hedge| self.half_edges[*hedge].face.is_null());
+
+            let hedge = match (free, self.find_half_edge(v1, v2)) {
+                (Some(free), _) => free,
+                (None, Some(used)) => {
+                    let copy = self
+                        .copy_edge(v1, v2)
+                        .ok_or_else(|| self.no_free_half_edge(v1, v2))?;
+
+                    let key = (v1.min(v2), v1.max(v2));
+                    let index = *glue_index.entry(key).or_insert_with(|| {
+                        glued.push(GluedEdge {
+                            vertices: [v1, v2],
+                            edges: vec![self.half_edges[used].edge],
+                        });
+                        glued.len() - 1
+                    });
+                    glued[index].edges.push(self.half_edges[copy].edge);
+
+                    copy
+                }
+                (None, None) => {
+                    let edge = self
+                        .new_edge(v1, v2, Default::default())
+                        .ok_or_else(|| self.no_free_half_edge(v1, v2))?;
+                    self.edges[edge].hedge
+                }
+            };
+            hedges.push(hedge);
+        }
+
+        self.new_face_from_half_edges(&hedges, Default::default())
+    }
+
+    /// The error for a edge between `v1` & `v2` that can't be inserted, since one of them is
+    /// surrounded by faces
+    fn no_free_half_edge(&self, v1: VertexHandle, v2: VertexHandle) -> FaceError {
+        let surrounded = |vertex: VertexHandle| {
+            let hedge = self.vertices[vertex].hedge;
+            !hedge.is_null() && self.find_free_half_edge(hedge).is_none()
+        };
+        FaceError::NoFreeHalfEdge(if surrounded(v1) { v1 } else { v2 })
+    }
+
+    /// Creates a new edge from `v1` to `v2`, even if there already is a edge between them.
+    /// Returns the half edge going from `v1` to `v2`.
+    fn copy_edge(&mut self, v1: VertexHandle, v2: VertexHandle) -> Option<HalfEdgeHandle> {
+        let v1_insertion = self.find_free_half_edge(self.vertices[v1].hedge)?;
+        let v2_insertion = self.find_free_half_edge(self.vertices[v2].hedge)?;
+
+        let edge = self.insert_edge(
+            v1,
+            v2,
+            Default::default(),
+            Some(v1_insertion),
+            Some(v2_insertion),
+        );
+        Some(self.edges[edge].hedge)
+    }
+}
@@ -0,0 +1,411 @@
+//! Standard meshes, for tests, demos and benchmarks
+//!
+//! The position of each vertex is passed to a closure that creates the vertex data.
+//! The faces of closed meshes are counter-clockwise when seen from the outside, and the open
+//! meshes ([grid] & [disc]) lie in the xy plane and are counter-clockwise when seen from +z.
+//!
+//! ```
+//! use half_edge_graph::{primitives, HalfEdgeGraph};
+//!
+//! #[derive(Default)]
+//! struct Data;
+//! impl half_edge_graph::Data for Data {
+//!     type Face = ();
+//!     type Edge = ();
+//!     type HalfEdge = ();
+//!     type Vertex = [f64; 3];
+//! }
+//!
+//! let sphere: HalfEdgeGraph<Data> = primitives::icosphere(1, |position| position);
+//! assert_eq!(sphere.iter_faces().count(), 80);
+//! ```
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use crate::{Data, HalfEdgeGraph};
+
+type Position = [f64; 3];
+
+fn build<DataTypes, F>(
+    positions: &[Position],
+    polygons: &[Vec<usize>],
+    mut position: F,
+) -> HalfEdgeGraph<DataTypes>
+where
+    DataTypes: Data + Default,
+    F: FnMut(Position) -> DataTypes::Vertex,
+{
+    let mut graph = HalfEdgeGraph::default();
+    let vertices: Vec<_> = positions
+        .iter()
+        .map(|p| graph.new_vertex(position(*p)))
+        .collect();
+    for polygon in polygons {
+        let polygon: Vec<_> = polygon.iter().map(|index| vertices[*index]).collect();
+        graph
+            .new_face(&polygon, Default::default())
+            .expect("faces of a primitive fits together");
+    }
+    graph
+}
+
+/// A point on the unit circle in the xy plane, at `z`
+fn circle(index: usize, segments: usize, z: f64) -> Position {
+    let angle = 2. * PI * index as f64 / segments as f64;
+    [angle.cos(), angle.sin(), z]
+}
+
+/// A grid of `rows` x `columns` unit quads, from the origin to `[columns, rows, 0]`.
+pub fn grid<DataTypes, F>(rows: usize, columns: usize, position: F) -> HalfEdgeGraph<DataTypes>
+where
+    DataTypes: Data + Default,
+    F: FnMut(Position) -> DataTypes::Vertex,
+{
+    let index = |row: usize, column: usize| row * (columns + 1) + column;
+
+    let positions: Vec<_> = (0..=rows)
+        .flat_map(|row| (0..=columns).map(move |column| [column as f64, row as f64, 0.]))
+        .collect();
+    let polygons: Vec<_> = (0..rows)
+        .flat_map(|row| {
+            (0..columns).map(move |column| {
+                vec![
+                    index(row, column),
+                    index(row, column + 1),
+                    index(row + 1, column + 1),
+                    index(row + 1, column),
+                ]
+            })
+        })
+        .collect();
+
+    build(&positions, &polygons, position)
+}
+
+/// A disc with radius 1, made of a fan of `segments` triangles around the origin.
+///
+/// Panics if `segments` is less than 3.
+pub fn disc<DataTypes, F>(segments: usize, position: F) -> HalfEdgeGraph<DataTypes>
+where
+    DataTypes: Data + Default,
+    F: FnMut(Position) -> DataTypes::Vertex,
+{
+    assert!(segments >= 3, "a disc needs at least 3 segments");
+
+    let mut positions = vec![[0.; 3]];
+    positions.extend((0..segments).map(|i| circle(i, segments, 0.)));
+    let polygons: Vec<_> = (0..segments)
+        .map(|i| vec![0, 1 + i, 1 + (i + 1) % segments])
+        .collect();
+
+    build(&positions, &polygons, position)
+}
+
+/// A cube from `[-1, -1, -1]` to `[1, 1, 1]`, with 6 quads.
+pub fn cube<DataTypes, F>(position: F) -> HalfEdgeGraph<DataTypes>
+where
+    DataTypes: Data + Default,
+    F: FnMut(Position) -> DataTypes::Vertex,
+{
+    // The bits of the index are the x, y & z coordinates
+    let positions: Vec<_> = (0..8)
+        .map(|i| {
+            let coordinate = |bit: usize| if i & (1 << bit) == 0 { -1. } else { 1. };
+            [coordinate(0), coordinate(1), coordinate(2)]
+        })
+        .collect();
+    let polygons = vec![
+        vec![0, 2, 3, 1],
+        vec![4, 5, 7, 6],
+        vec![0, 1, 5, 4],
+        vec![2, 6, 7, 3],
+        vec![0, 4, 6, 2],
+        vec![1, 3, 7, 5],
+    ];
+
+    build(&positions, &polygons, position)
+}
+
+/// A prism with regular `sides`-gons as the bottom and top, at z -1 and 1, with radius 1.
+///
+/// The bottom and top are single faces, see [cylinder] for triangulated caps.
+/// Panics if `sides` is less than 3.
+pub fn prism<DataTypes, F>(sides: usize, position: F) -> HalfEdgeGraph<DataTypes>
+where
+    DataTypes: Data + Default,
+    F: FnMut(Position) -> DataTypes::Vertex,
+{
+    assert!(sides >= 3, "a prism needs at least 3 sides");
+
+    let positions: Vec<_> = (0..sides)
+        .map(|i| circle(i, sides, -1.))
+        .chain((0..sides).map(|i| circle(i, sides, 1.)))
+        .collect();
+
+    let mut polygons = vec![(0..sides).rev().collect(), (sides..2 * sides).collect()];
+    polygons.extend((0..sides).map(|i| {
+        let next = (i + 1) % sides;
+        vec![i, next, sides + next, sides + i]
+    }));
+
+    build(&positions, &polygons, position)
+}
+
+/// A cylinder along the z axis from -1 to 1 with radius 1.
+///
+/// The side is split into `segments` around and `stacks` along the axis,
+/// and the caps are fans of triangles around their center.
+/// Panics if `segments` is less than 3 or `stacks` is 0.
+pub fn cylinder<DataTypes, F>(
+    segments: usize,
+    stacks: usize,
+    position: F,
+) -> HalfEdgeGraph<DataTypes>
+where
+    DataTypes: Data + Default,
+    F: FnMut(Position) -> DataTypes::Vertex,
+{
+    assert!(segments >= 3, "a cylinder needs at least 3 segments");
+    assert!(stacks >= 1, "a cylinder needs at least 1 stack");
+
+    let index = |stack: usize, i: usize| stack * segments + i % segments;
+    let mut positions: Vec<_> = (0..=stacks)
+        .flat_map(|stack| {
+            let z = -1. + 2. * stack as f64 / stacks as f64;
+            (0..segments).map(move |i| circle(i, segments, z))
+        })
+        .collect();
+    let bottom = positions.len();
+    let top = bottom + 1;
+    positions.push([0., 0., -1.]);
+    positions.push([0., 0., 1.]);
+
+    let mut polygons = Vec::new();
+    for i in 0..segments {
+        polygons.push(vec![bottom, index(0, i + 1), index(0, i)]);
+    }
+    for stack in 0..stacks {
+        for i in 0..segments {
+            polygons.push(vec![
+                index(stack, i),
+                index(stack, i + 1),
+                index(stack + 1, i + 1),
+                index(stack + 1, i),
+            ]);
+        }
+    }
+    for i in 0..segments {
+        polygons.push(vec![top, index(stacks, i), index(stacks, i + 1)]);
+    }
+
+    build(&positions, &polygons, position)
+}
+
+/// A cone with its base at z -1 with radius 1, and its apex at `[0, 0, 1]`.
+///
+/// The base is a fan of triangles around its center.
+/// Panics if `segments` is less than 3.
+pub fn cone<DataTypes, F>(segments: usize, position: F) -> HalfEdgeGraph<DataTypes>
+where
+    DataTypes: Data + Default,
+    F: FnMut(Position) -> DataTypes::Vertex,
+{
+    assert!(segments >= 3, "a cone needs at least 3 segments");
+
+    let mut positions: Vec<_> = (0..segments).map(|i| circle(i, segments, -1.)).collect();
+    let (apex, center) = (segments, segments + 1);
+    positions.push([0., 0., 1.]);
+    positions.push([0., 0., -1.]);
+
+    let mut polygons = Vec::new();
+    for i in 0..segments {
+        polygons.push(vec![i, (i + 1) % segments, apex]);
+    }
+    for i in 0..segments {
+        polygons.push(vec![center, (i + 1) % segments, i]);
+    }
+
+    build(&positions, &polygons, position)
+}
+
+/// A sphere with radius 1, split into `segments` around the z axis and `rings` from pole to pole.
+///
+/// The faces around the poles are triangles, the rest are quads.
+/// Panics if `segments` is less than 3 or `rings` is less than 2.
+pub fn uv_sphere<DataTypes, F>(
+    segments: usize,
+    rings: usize,
+    position: F,
+) -> HalfEdgeGraph<DataTypes>
+where
+    DataTypes: Data + Default,
+    F: FnMut(Position) -> DataTypes::Vertex,
+{
+    assert!(segments >= 3, "a sphere needs at least 3 segments");
+    assert!(rings >= 2, "a sphere needs at least 2 rings");
+
+    // The rings of vertices between the poles, from the north pole
+    let index = |ring: usize, i: usize| ring * segments + i % segments;
+    let mut positions: Vec<_> = (1..rings)
+        .flat_map(|ring| {
+            let polar = PI * ring as f64 / rings as f64;
+            (0..segments).map(move |i| {
+                let [x, y, _] = circle(i, segments, 0.);
+                [x * polar.sin(), y * polar.sin(), polar.cos()]
+            })
+        })
+        .collect();
+    let north = positions.len();
+    let south = north + 1;
+    positions.push([0., 0., 1.]);
+    positions.push([0., 0., -1.]);
+
+    let last = rings - 2;
+    let mut polygons = Vec::new();
+    for i in 0..segments {
+        polygons.push(vec![north, index(0, i), index(0, i + 1)]);
+    }
+    for ring in 0..last {
+        for i in 0..segments {
+            polygons.push(vec![
+                index(ring, i),
+                index(ring + 1, i),
+                index(ring + 1, i + 1),
+                index(ring, i + 1),
+            ]);
+        }
+    }
+    for i in 0..segments {
+        polygons.push(vec![south, index(last, i + 1), index(last, i)]);
+    }
+
+    build(&positions, &polygons, position)
+}
+
+/// A sphere with radius 1, made by splitting each triangle of a icosahedron into 4,
+/// `subdivisions` times.
+pub fn icosphere<DataTypes, F>(subdivisions: usize, position: F) -> HalfEdgeGraph<DataTypes>
+where
+    DataTypes: Data + Default,
+    F: FnMut(Position) -> DataTypes::Vertex,
+{
+    let normalize = |[x, y, z]: Position| {
+        let length = (x * x + y * y + z * z).sqrt();
+        [x / length, y / length, z / length]
+    };
+
+    let t = (1. + 5f64.sqrt()) / 2.;
+    let mut positions: Vec<_> = [
+        [-1., t, 0.],
+        [1., t, 0.],
+        [-1., -t, 0.],
+        [1., -t, 0.],
+        [0., -1., t],
+        [0., 1., t],
+        [0., -1., -t],
+        [0., 1., -t],
+        [t, 0., -1.],
+        [t, 0., 1.],
+        [-t, 0., -1.],
+        [-t, 0., 1.],
+    ]
+    .iter()
+    .map(|p| normalize(*p))
+    .collect();
+
+    let mut triangles = vec![
+        [0, 11, 5],
+        [0, 5, 1],
+        [0, 1, 7],
+        [0, 7, 10],
+        [0, 10, 11],
+        [1, 5, 9],
+        [5, 11, 4],
+        [11, 10, 2],
+        [10, 7, 6],
+        [7, 1, 8],
+        [3, 9, 4],
+        [3, 4, 2],
+        [3, 2, 6],
+        [3, 6, 8],
+        [3, 8, 9],
+        [4, 9, 5],
+        [2, 4, 11],
+        [6, 2, 10],
+        [8, 6, 7],
+        [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        let mut midpoints = HashMap::new();
+        let mut midpoint = |a: usize, b: usize| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                let (pa, pb) = (positions[a], positions[b]);
+                positions.push(normalize([
+                    (pa[0] + pb[0]) / 2.,
+                    (pa[1] + pb[1]) / 2.,
+                    (pa[2] + pb[2]) / 2.,
+                ]));
+                positions.len() - 1
+            })
+        };
+
+        triangles = triangles
+            .iter()
+            .flat_map(|&[a, b, c]| {
+                let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+                vec![[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+            })
+            .collect();
+    }
+
+    let polygons: Vec<_> = triangles.iter().map(|t| t.to_vec()).collect();
+    build(&positions, &polygons, position)
+}
+
+/// A torus around the z axis, split into `major_segments` around the z axis and
+/// `minor_segments` around the tube.
+///
+/// Panics if either number of segments is less than 3.
+pub fn torus<DataTypes, F>(
+    major_segments: usize,
+    minor_segments: usize,
+    major_radius: f64,
+    minor_radius: f64,
+    position: F,
+) -> HalfEdgeGraph<DataTypes>
+where
+    DataTypes: Data + Default,
+    F: FnMut(Position) -> DataTypes::Vertex,
+{
+    assert!(
+        major_segments >= 3 && minor_segments >= 3,
+        "a torus needs at least 3 segments"
+    );
+
+    let index = |i: usize, j: usize| (i % major_segments) * minor_segments + j % minor_segments;
+    let positions: Vec<_> = (0..major_segments)
+        .flat_map(|i| {
+            let [x, y, _] = circle(i, major_segments, 0.);
+            (0..minor_segments).map(move |j| {
+                let [u, v, _] = circle(j, minor_segments, 0.);
+                let radius = major_radius + minor_radius * u;
+                [x * radius, y * radius, minor_radius * v]
+            })
+        })
+        .collect();
+    let polygons: Vec<_> = (0..major_segments)
+        .flat_map(|i| {
+            (0..minor_segments).map(move |j| {
+                vec![
+                    index(i, j),
+                    index(i + 1, j),
+                    index(i + 1, j + 1),
+                    index(i, j + 1),
+                ]
+            })
+        })
+        .collect();
+
+    build(&positions, &polygons, position)
+}
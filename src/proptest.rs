@@ -0,0 +1,236 @@
+//! Strategies for generating random valid graphs and random sequences of operations with [proptest](::proptest)
+//!
+//! All items are created with their default data. The graphs are built from a list of random
+//! choices, so they shrink to smaller graphs when a test fails.
+//!
+//! ```
+//! use half_edge_graph::proptest::planar_triangulation;
+//! use proptest::prelude::*;
+//! use proptest::test_runner::TestRunner;
+//!
+//! TestRunner::default()
+//!     .run(&planar_triangulation::<()>(1..20), |graph| {
+//!         prop_assert!(graph.validate().is_ok());
+//!
+//!         // Euler characteristic of a disk
+//!         let (v, e, f) = (
+//!             graph.iter_vertices().count(),
+//!             graph.iter_edges().count(),
+//!             graph.iter_faces().count(),
+//!         );
+//!         prop_assert_eq!(v + f, e + 1);
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! ```
+use std::fmt::Debug;
+use std::ops::Range;
+
+use ::proptest::collection::vec;
+use ::proptest::prelude::*;
+
+use crate::{Data, HalfEdgeGraph, VertexHandle};
+
+/// Graphs with only vertices and edges.
+///
+/// The edges are between random vertices, edges that aren't allowed by [Data::ALLOW_PARALLEL_EDGES]
+/// and [Data::ALLOW_SELF_LOOPS] are skipped.
+pub fn edge_graph<DataTypes>(
+    vertices: Range<usize>,
+    edges: Range<usize>,
+) -> impl Strategy<Value = HalfEdgeGraph<DataTypes>>
+where
+    DataTypes: Data + Default + Debug,
+    DataTypes::Vertex: Debug,
+    DataTypes::Edge: Debug,
+    DataTypes::HalfEdge: Debug,
+    DataTypes::Face: Debug,
+{
+    (vertices, vec((any::<usize>(), any::<usize>()), edges)).prop_map(|(count, edges)| {
+        let mut graph = HalfEdgeGraph::default();
+        let vertices: Vec<_> = (0..count)
+            .map(|_| graph.new_vertex(Default::default()))
+            .collect();
+        if count > 0 {
+            for (a, b) in edges {
+                graph.new_edge(vertices[a % count], vertices[b % count], Default::default());
+            }
+        }
+        graph
+    })
+}
+
+/// Triangulations of a disk, with the number of triangles in `triangles`.
+///
+/// Starting from a single triangle, each step either adds a new vertex outside of a boundary edge,
+/// or fills the gap between two adjacent boundary edges. So the boundary is always a single loop.
+pub fn planar_triangulation<DataTypes>(
+    triangles: Range<usize>,
+) -> impl Strategy<Value = HalfEdgeGraph<DataTypes>>
+where
+    DataTypes: Data + Default + Debug,
+    DataTypes::Vertex: Debug,
+    DataTypes::Edge: Debug,
+    DataTypes::HalfEdge: Debug,
+    DataTypes::Face: Debug,
+{
+    // The first triangle is always added
+    let steps = triangles.start.saturating_sub(1)..triangles.end.max(2) - 1;
+    vec((any::<bool>(), any::<usize>()), steps).prop_map(|steps| {
+        let mut graph = HalfEdgeGraph::default();
+
+        // The boundary in the same direction as the half edges of the faces
+        let mut boundary: Vec<VertexHandle> = (0..3)
+            .map(|_| graph.new_vertex(Default::default()))
+            .collect();
+        graph
+            .new_face(&boundary, Default::default())
+            .expect("first triangle");
+
+        for (fill, index) in steps {
+            let len = boundary.len();
+            let i = index % len;
+            let (a, b, c) = (
+                boundary[i],
+                boundary[(i + 1) % len],
+                boundary[(i + 2) % len],
+            );
+
+            if fill && len > 3 && graph.find_edge(a, c).is_none() {
+                graph
+                    .new_face(&[c, b, a], Default::default())
+                    .expect("triangle filling the boundary");
+                boundary.remove((i + 1) % len);
+            } else {
+                let vertex = graph.new_vertex(Default::default());
+                graph
+                    .new_face(&[b, a, vertex], Default::default())
+                    .expect("triangle outside the boundary");
+                boundary.insert(i + 1, vertex);
+            }
+        }
+        graph
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Cell {
+    Hole,
+    Quad,
+    Triangles,
+}
+
+/// Grids of quads and triangles with holes, with the number of rows and columns of cells in the ranges.
+///
+/// Each cell is either a quad, two triangles or a hole. Cells that only touch at a corner
+/// make a non-manifold vertex, where faces can't always be added, those faces are left out.
+pub fn polygon_mesh<DataTypes>(
+    rows: Range<usize>,
+    columns: Range<usize>,
+) -> impl Strategy<Value = HalfEdgeGraph<DataTypes>>
+where
+    DataTypes: Data + Default + Debug,
+    DataTypes::Vertex: Debug,
+    DataTypes::Edge: Debug,
+    DataTypes::HalfEdge: Debug,
+    DataTypes::Face: Debug,
+{
+    let cell = prop_oneof![
+        1 => Just(Cell::Hole),
+        3 => Just(Cell::Quad),
+        2 => Just(Cell::Triangles),
+    ];
+    (rows, columns).prop_flat_map(move |(rows, columns)| {
+        vec(cell.clone(), rows * columns).prop_map(move |cells| {
+            let mut graph = HalfEdgeGraph::default();
+            let vertices: Vec<_> = (0..(rows + 1) * (columns + 1))
+                .map(|_| graph.new_vertex(Default::default()))
+                .collect();
+            let vertex = |row: usize, column: usize| vertices[row * (columns + 1) + column];
+
+            let mut polygons = Vec::new();
+            for (index, cell) in cells.into_iter().enumerate() {
+                let (row, column) = (index / columns, index % columns);
+                let corners = [
+                    vertex(row, column),
+                    vertex(row, column + 1),
+                    vertex(row + 1, column + 1),
+                    vertex(row + 1, column),
+                ];
+                match cell {
+                    Cell::Hole => {}
+                    Cell::Quad => polygons.push(corners.to_vec()),
+                    Cell::Triangles => {
+                        polygons.push(vec![corners[0], corners[1], corners[2]]);
+                        polygons.push(vec![corners[0], corners[2], corners[3]]);
+                    }
+                }
+            }
+            graph.add_polygons(&polygons);
+            graph
+        })
+    })
+}
+
+/// A operation on a graph, generated by [operations]
+///
+/// The indices select items in iteration order, modulo the number of items.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    NewVertex,
+    NewEdge(usize, usize),
+    NewFace(Vec<usize>),
+    ReverseFace(usize),
+    ReverseComponent(usize),
+    SplitNonManifoldVertices,
+}
+
+impl Operation {
+    /// Applies the operation to the graph, operations that fails or doesn't have any items
+    /// to select are ignored.
+    pub fn apply<DataTypes: Data>(&self, graph: &mut HalfEdgeGraph<DataTypes>)
+    where
+        DataTypes::Vertex: Clone,
+    {
+        let vertices: Vec<_> = graph.vertices.keys().collect();
+        let faces: Vec<_> = graph.faces.keys().collect();
+        let vertex = |index: usize| vertices[index % vertices.len()];
+        let face = |index: usize| faces[index % faces.len()];
+
+        match self {
+            Operation::NewVertex => {
+                graph.new_vertex(Default::default());
+            }
+            Operation::NewEdge(a, b) if !vertices.is_empty() => {
+                graph.new_edge(vertex(*a), vertex(*b), Default::default());
+            }
+            Operation::NewFace(indices) if !vertices.is_empty() => {
+                let polygon: Vec<_> = indices.iter().map(|index| vertex(*index)).collect();
+                graph.new_face(&polygon, Default::default());
+            }
+            Operation::ReverseFace(index) if !faces.is_empty() => {
+                let _ = graph.reverse_face(face(*index));
+            }
+            Operation::ReverseComponent(index) if !faces.is_empty() => {
+                let _ = graph.reverse_component(face(*index));
+            }
+            Operation::SplitNonManifoldVertices => {
+                graph.split_non_manifold_vertices();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Sequences of random operations, with the number of operations in `len`
+pub fn operations(len: Range<usize>) -> impl Strategy<Value = Vec<Operation>> {
+    let operation = prop_oneof![
+        3 => Just(Operation::NewVertex),
+        3 => (any::<usize>(), any::<usize>()).prop_map(|(a, b)| Operation::NewEdge(a, b)),
+        3 => vec(any::<usize>(), 3..6).prop_map(Operation::NewFace),
+        1 => any::<usize>().prop_map(Operation::ReverseFace),
+        1 => any::<usize>().prop_map(Operation::ReverseComponent),
+        1 => Just(Operation::SplitNonManifoldVertices),
+    ];
+    vec(operation, len)
+}
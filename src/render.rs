@@ -0,0 +1,275 @@
+//! Headless rendering of planar graphs and 2D meshes
+//!
+//! ```
+//! use half_edge_graph::{render, HalfEdgeGraph};
+//!
+//! #[derive(Default)]
+//! struct Data;
+//! impl half_edge_graph::Data for Data {
+//!     type Face = ();
+//!     type Edge = ();
+//!     type HalfEdge = ();
+//!     type Vertex = [f64; 2];
+//! }
+//!
+//! let mut graph = HalfEdgeGraph::<Data>::default();
+//! let v1 = graph.new_vertex([0., 0.]);
+//! let v2 = graph.new_vertex([1., 0.]);
+//! let v3 = graph.new_vertex([0., 1.]);
+//! graph.new_face(&[v1, v2, v3], ()).unwrap();
+//!
+//! let options = render::SvgOptions::default()
+//!     .half_edges(true)
+//!     .highlight(vec![v2.into()]);
+//! let svg = render::svg(&graph, |vertex| *vertex, &options);
+//! assert!(svg.starts_with("<svg"));
+//! ```
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use slotmap::SecondaryMap;
+
+use crate::io::escape_xml;
+use crate::{Data, HalfEdgeGraph, Handle, VertexFn};
+
+const COLOR: &str = "#333";
+const FACE_COLOR: &str = "#ddd";
+const HALF_EDGE_COLOR: &str = "#36c";
+const HIGHLIGHT_COLOR: &str = "#e22";
+const FACE_HIGHLIGHT_COLOR: &str = "#fc8";
+
+/// Options for [svg]
+#[derive(Debug, Clone, Default)]
+pub struct SvgOptions {
+    half_edges: bool,
+    labels: bool,
+    highlight: HashSet<Handle>,
+}
+
+impl SvgOptions {
+    /// Draw the half edges as arrows inside their faces, pointing towards their vertex so that they
+    /// follow the `next` links. Half edges without a face are drawn to the left of their edge.
+    pub fn half_edges(mut self, enabled: bool) -> Self {
+        self.half_edges = enabled;
+        self
+    }
+
+    /// Label the items with their handles
+    pub fn labels(mut self, enabled: bool) -> Self {
+        self.labels = enabled;
+        self
+    }
+
+    /// Draw the items in a highlight color
+    pub fn highlight(mut self, handles: impl IntoIterator<Item = Handle>) -> Self {
+        self.highlight.extend(handles);
+        self
+    }
+}
+
+type Point = [f64; 2];
+
+fn lerp(a: Point, b: Point, t: f64) -> Point {
+    [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t]
+}
+
+/// Renders the graph as a SVG image, with the vertices placed by `positions`.
+///
+/// The positions use a y axis pointing up, so counter-clockwise faces are counter-clockwise in the image.
+/// The image is scaled to fit the graph.
+pub fn svg<DataTypes, P>(
+    graph: &HalfEdgeGraph<DataTypes>,
+    positions: P,
+    options: &SvgOptions,
+) -> String
+where
+    DataTypes: Data,
+    P: Fn(VertexFn<'_, DataTypes>) -> Point,
+{
+    let mut points = SecondaryMap::new();
+    for vertex in graph.iter_vertices() {
+        points.insert(vertex.handle(), positions(vertex));
+    }
+
+    let mut min = [f64::INFINITY; 2];
+    let mut max = [f64::NEG_INFINITY; 2];
+    for point in points.values() {
+        for i in 0..2 {
+            min[i] = min[i].min(point[i]);
+            max[i] = max[i].max(point[i]);
+        }
+    }
+    if points.is_empty() {
+        min = [0.; 2];
+        max = [0.; 2];
+    }
+    let size = (max[0] - min[0]).max(max[1] - min[1]);
+    let size = if size > 0. { size } else { 1. };
+    // Size of the vertices, arrows and text
+    let unit = size / 100.;
+    let margin = unit * 5.;
+
+    let highlighted = |handle: Handle| options.highlight.contains(&handle);
+    let color = |handle: Handle, color| {
+        if highlighted(handle) {
+            HIGHLIGHT_COLOR
+        } else {
+            color
+        }
+    };
+    // Flip the y axis
+    let point = |[x, y]: Point| format!("{} {}", x, -y);
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        min[0] - margin,
+        -max[1] - margin,
+        max[0] - min[0] + 2. * margin,
+        max[1] - min[1] + 2. * margin
+    )
+    .unwrap();
+
+    if options.half_edges {
+        writeln!(svg, "  <defs>").unwrap();
+        for (id, color) in [
+            ("arrow", HALF_EDGE_COLOR),
+            ("arrow-highlight", HIGHLIGHT_COLOR),
+        ] {
+            writeln!(
+                svg,
+                r#"    <marker id="{}" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="4" markerHeight="4" orient="auto"><path d="M 0 0 L 10 5 L 0 10 z" fill="{}"/></marker>"#,
+                id, color
+            )
+            .unwrap();
+        }
+        writeln!(svg, "  </defs>").unwrap();
+    }
+
+    let mut labels = Vec::new();
+    let mut centroids = SecondaryMap::new();
+    for face in graph.iter_faces() {
+        let corners: Vec<_> = face.vertices().map(|v| points[v.handle()]).collect();
+        let count = corners.len() as f64;
+        let centroid = corners.iter().fold([0.; 2], |sum, p| {
+            [sum[0] + p[0] / count, sum[1] + p[1] / count]
+        });
+        centroids.insert(face.handle(), centroid);
+
+        let handle = face.handle().into();
+        let fill = if highlighted(handle) {
+            FACE_HIGHLIGHT_COLOR
+        } else {
+            FACE_COLOR
+        };
+        let corners: Vec<_> = corners.into_iter().map(point).collect();
+        writeln!(
+            svg,
+            r#"  <polygon points="{}" fill="{}" stroke="none"/>"#,
+            corners.join(" "),
+            fill
+        )
+        .unwrap();
+        labels.push((centroid, handle));
+    }
+
+    for edge in graph.iter_edges() {
+        let [v1, v2] = edge.vertices();
+        let (a, b) = (points[v1.handle()], points[v2.handle()]);
+        let handle = edge.handle().into();
+        let width = if highlighted(handle) { 0.6 } else { 0.3 };
+        writeln!(
+            svg,
+            r#"  <path d="M {} L {}" stroke="{}" stroke-width="{}"/>"#,
+            point(a),
+            point(b),
+            color(handle, COLOR),
+            width * unit
+        )
+        .unwrap();
+        labels.push((lerp(a, b, 0.5), handle));
+    }
+
+    if options.half_edges {
+        for hedge in graph.iter_half_edges() {
+            let tail = points[hedge.pair().vertex().handle()];
+            let head = points[hedge.vertex().handle()];
+            let (tail, head) = match hedge.face().and_then(|face| centroids.get(face.handle())) {
+                Some(centroid) => (lerp(tail, *centroid, 0.2), lerp(head, *centroid, 0.2)),
+                None => {
+                    let direction = [head[0] - tail[0], head[1] - tail[1]];
+                    let length = direction[0].hypot(direction[1]);
+                    let offset = if length > 0. {
+                        let scale = 1.5 * unit / length;
+                        [-direction[1] * scale, direction[0] * scale]
+                    } else {
+                        [0.; 2]
+                    };
+                    (
+                        [tail[0] + offset[0], tail[1] + offset[1]],
+                        [head[0] + offset[0], head[1] + offset[1]],
+                    )
+                }
+            };
+            let (tail, head) = (lerp(tail, head, 0.1), lerp(tail, head, 0.9));
+
+            let handle = hedge.handle().into();
+            let marker = if highlighted(handle) {
+                "arrow-highlight"
+            } else {
+                "arrow"
+            };
+            writeln!(
+                svg,
+                r#"  <path d="M {} L {}" stroke="{}" stroke-width="{}" marker-end="url(#{})"/>"#,
+                point(tail),
+                point(head),
+                color(handle, HALF_EDGE_COLOR),
+                0.2 * unit,
+                marker
+            )
+            .unwrap();
+            labels.push((lerp(tail, head, 0.5), handle));
+        }
+    }
+
+    for vertex in graph.iter_vertices() {
+        let position = points[vertex.handle()];
+        let handle = vertex.handle().into();
+        writeln!(
+            svg,
+            r#"  <circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+            position[0],
+            -position[1],
+            unit,
+            color(handle, COLOR)
+        )
+        .unwrap();
+        labels.push((position, handle));
+    }
+
+    if options.labels {
+        for ([x, y], handle) in labels {
+            let text = match handle {
+                Handle::Vertex(handle) => format!("{:?}", handle),
+                Handle::Edge(handle) => format!("{:?}", handle),
+                Handle::HalfEdge(handle) => format!("{:?}", handle),
+                Handle::Face(handle) => format!("{:?}", handle),
+            };
+            writeln!(
+                svg,
+                r#"  <text x="{}" y="{}" font-size="{}" text-anchor="middle" fill="{}">{}</text>"#,
+                x,
+                -y,
+                2. * unit,
+                color(handle, COLOR),
+                escape_xml(&text)
+            )
+            .unwrap();
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
@@ -6,50 +6,9 @@ use super::{EdgeHandle, FaceHandle, VertexHandle};
 use std::collections::{HashMap, HashSet};
 
 impl<DataTypes: super::Data> super::HalfEdgeGraph<DataTypes> {
-    #[allow(unused)]
-    pub fn dump_to_cytoscape(&self) {
-        println!("var data = {{");
-        println!("  vertices: {{");
-        for (handle, data) in &self.vertices {
-            if data.hedge.is_null() {
-                println!("    '{:?}': {{hedge: null}},", handle);
-            } else {
-                println!("    '{:?}': {{hedge: '{:?}'}},", handle, data.hedge);
-            }
-        }
-        println!("  }},");
-
-        println!("  edges: {{");
-        for (handle, data) in &self.edges {
-            println!("    '{:?}': {{hedge: '{:?}'}},", handle, data.hedge);
-        }
-        println!("  }},");
-
-        println!("  faces: {{");
-        for (handle, data) in &self.faces {
-            println!("    '{:?}': {{hedge: '{:?}'}},", handle, data.hedge);
-        }
-        println!("  }},");
-
-        println!("  half_edges: {{");
-        for (handle, data) in &self.half_edges {
-            println!(
-                "    '{:?}': {{pair: '{:?}', next: '{:?}', prev: '{:?}', vertex: '{:?}', edge: '{:?}', face: '{:?}'}},",
-                handle, data.pair, data.next, data.prev, data.vertex, data.edge, data.face
-            );
-        }
-        println!("  }},");
-        println!("}};");
-    }
-
     pub fn verify_invarians(&self) {
-        for hedge in self.iter_half_edges() {
-            assert!(hedge == hedge.pair().pair());
-            assert!(hedge == hedge.next().prev());
-            assert!(hedge.face() == hedge.next().face());
-            assert!(hedge.edge() == hedge.pair().edge());
-            assert!(hedge.vertex() != hedge.pair().vertex());
-            assert!(hedge.vertex() == hedge.next().pair().vertex());
+        if let Err(error) = self.validate() {
+            panic!("{}", error);
         }
     }
 }
@@ -530,3 +489,1843 @@ test_mesh! {
         face f4 (v7 -> v8 -> v4);
     }
 }
+
+#[cfg(feature = "petgraph")]
+#[test]
+fn petgraph_algorithms() {
+    use petgraph::visit::{EdgeRef, IntoEdges, IntoNeighbors, NodeIndexable};
+
+    let mut mesh = HalfEdgeGraph::default();
+    let v1 = mesh.new_vertex(());
+    let v2 = mesh.new_vertex(());
+    let v3 = mesh.new_vertex(());
+    let v4 = mesh.new_vertex(());
+    let v5 = mesh.new_vertex(());
+
+    mesh.new_face(&[v1, v2, v3], ()).unwrap();
+    mesh.new_edge(v3, v4, ()).unwrap();
+
+    let neighbors: HashSet<_> = (&mesh).neighbors(v3).collect();
+    assert_eq!(neighbors, [v1, v2, v4].iter().copied().collect());
+
+    for edge in (&mesh).edges(v3) {
+        assert_eq!(edge.source(), v3);
+        assert!(neighbors.contains(&edge.target()));
+    }
+
+    for v in &[v1, v2, v3, v4, v5] {
+        assert_eq!(mesh.from_index(mesh.to_index(*v)), *v);
+        assert!(mesh.to_index(*v) < mesh.node_bound());
+    }
+    let indices: Vec<_> = [v1, v2, v3, v4, v5]
+        .iter()
+        .map(|v| mesh.to_index(*v))
+        .collect();
+    assert_eq!(indices, vec![1, 2, 3, 4, 5]);
+
+    // The indices rely on the undocumented layout of the slotmap keys,
+    // a reused slot must get the same index with a new version
+    let mut slots = slotmap::SlotMap::<VertexHandle, ()>::with_key();
+    let first = slots.insert(());
+    slots.remove(first);
+    let reused = slots.insert(());
+    assert_ne!(first, reused);
+    assert_eq!(crate::petgraph::key_index(first), 1);
+    assert_eq!(crate::petgraph::key_index(reused), 1);
+    assert_eq!(crate::petgraph::key_index(slots.insert(())), 2);
+
+    assert_eq!(petgraph::algo::tarjan_scc(&mesh).len(), 2);
+    assert_eq!(petgraph::algo::min_spanning_tree(&mesh).count(), 5 + 3);
+    assert!(petgraph::algo::has_path_connecting(&mesh, v1, v4, None));
+    assert!(!petgraph::algo::has_path_connecting(&mesh, v1, v5, None));
+
+    let distances = petgraph::algo::dijkstra(&mesh, v1, None, |_| 1);
+    assert_eq!(distances[&v4], 2);
+
+    let graph = petgraph::Graph::from(&mesh);
+    assert_eq!(graph.node_count(), 5);
+    assert_eq!(graph.edge_count(), 4);
+
+    let copy = HalfEdgeGraph::from(graph);
+    assert_eq!(copy.iter_vertices().count(), 5);
+    assert_eq!(copy.iter_edges().count(), 4);
+    assert_eq!(copy.iter_faces().count(), 0);
+}
+
+#[cfg(feature = "geometry")]
+#[test]
+fn geometry_cube() {
+    use crate::geometry::NormalWeighting;
+
+    #[derive(Default)]
+    struct Data;
+    impl crate::Data for Data {
+        type Face = ();
+        type Edge = ();
+        type HalfEdge = ();
+        type Vertex = [f64; 3];
+    }
+
+    let mut mesh = super::HalfEdgeGraph::<Data>::default();
+    let v: Vec<_> = (0..8)
+        .map(|i| mesh.new_vertex([(i & 1) as f64, ((i >> 1) & 1) as f64, (i >> 2) as f64]))
+        .collect();
+
+    let faces = [
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+    ];
+    for face in &faces {
+        let vertices: Vec<_> = face.iter().map(|i| v[*i]).collect();
+        mesh.new_face(&vertices, ()).expect("Failed to create face");
+    }
+
+    let eps = 1e-9;
+    assert!((mesh.volume() - 1.).abs() < eps);
+    assert!((mesh.surface_area() - 6.).abs() < eps);
+    assert_eq!(mesh.bounding_box(), Some(([0., 0., 0.], [1., 1., 1.])));
+
+    for face in mesh.iter_faces() {
+        assert!(face.is_planar(eps));
+        assert!((face.area() - 1.).abs() < eps);
+
+        let normal = face.normal();
+        let center = face.centroid();
+        for i in 0..3 {
+            // normals point outwards from the center of the cube
+            assert!((normal[i] - (center[i] - 0.5) * 2.).abs() < eps);
+        }
+    }
+
+    for edge in mesh.iter_edges() {
+        assert!((edge.length() - 1.).abs() < eps);
+        let angle = edge.dihedral_angle().unwrap();
+        assert!((angle - std::f64::consts::FRAC_PI_2).abs() < eps);
+    }
+
+    let mut total_curvature = 0.;
+    for vertex in mesh.iter_vertices() {
+        let normal = vertex.normal(NormalWeighting::Angle);
+        let expected = 1. / 3f64.sqrt();
+        for i in 0..3 {
+            let sign = if vertex[i] > 0.5 { 1. } else { -1. };
+            assert!((normal[i] - sign * expected).abs() < eps);
+        }
+        assert!(vertex.mean_curvature().unwrap() > 0.);
+        assert!(vertex.gaussian_curvature().unwrap() > 0.);
+        total_curvature += vertex.angle_defect();
+    }
+    // Gauss-Bonnet
+    assert!((total_curvature - 4. * std::f64::consts::PI).abs() < eps);
+
+    // No area to divide the curvature by
+    let isolated = mesh.new_vertex([2., 2., 2.]);
+    let isolated = mesh.vertex(isolated).unwrap();
+    assert_eq!(isolated.gaussian_curvature(), None);
+    assert_eq!(isolated.mean_curvature(), None);
+
+    let v1 = mesh.new_vertex([3., 0., 0.]);
+    let v2 = mesh.new_vertex([4., 0., 0.]);
+    let v3 = mesh.new_vertex([5., 0., 0.]);
+    mesh.new_face(&[v1, v2, v3], ()).unwrap();
+    let degenerate = mesh.vertex(v2).unwrap();
+    assert_eq!(degenerate.gaussian_curvature(), None);
+    assert_eq!(degenerate.mean_curvature(), None);
+}
+
+#[test]
+fn planar_embedding_from_positions() {
+    /*
+     * mesh layout:
+     *                v5
+     *               /
+     *     v4 --- v3
+     *      |   / |
+     *      |  /  |
+     *     v1 --- v2
+     */
+    let mut mesh = HalfEdgeGraph::default();
+    let mut positions = HashMap::new();
+
+    let mut vertex = |mesh: &mut HalfEdgeGraph, x, y| {
+        let v = mesh.new_vertex(());
+        positions.insert(v, [x, y]);
+        v
+    };
+    let v1 = vertex(&mut mesh, 0., 0.);
+    let v2 = vertex(&mut mesh, 1., 0.);
+    let v3 = vertex(&mut mesh, 1., 1.);
+    let v4 = vertex(&mut mesh, 0., 1.);
+    let v5 = vertex(&mut mesh, 2., 2.);
+
+    for (a, b) in &[(v1, v2), (v3, v4), (v3, v5), (v2, v3), (v1, v3), (v4, v1)] {
+        mesh.new_edge(*a, *b, ()).unwrap();
+    }
+
+    let faces = mesh
+        .embed_planar(|v| positions[&v.handle()])
+        .expect("Failed to embed graph");
+    assert_eq!(faces.len(), 3);
+
+    let mut face_vertices: Vec<Vec<VertexHandle>> = faces
+        .iter()
+        .map(|f| {
+            let face = mesh.face(*f).unwrap();
+            let mut vertices: Vec<_> = face.vertices().map(|v| v.handle()).collect();
+            vertices.sort();
+            vertices
+        })
+        .collect();
+    face_vertices.sort();
+
+    let mut expected = vec![
+        vec![v1, v2, v3],
+        vec![v1, v3, v4],
+        // outer face, v3 is visited twice
+        vec![v1, v2, v3, v3, v4, v5],
+    ];
+    for vertices in &mut expected {
+        vertices.sort();
+    }
+    expected.sort();
+    assert_eq!(face_vertices, expected);
+
+    // Can't embed twice
+    assert!(mesh.embed_planar(|v| positions[&v.handle()]).is_none());
+
+    // Positions that aren't finite are rejected without changing the graph
+    let mut mesh = graph_from_edges(3, &[(0, 1), (1, 2)]);
+    let before = mesh.clone();
+    assert!(mesh.embed_planar(|_| [f64::NAN, 0.]).is_none());
+    assert!(mesh == before);
+}
+
+#[test]
+fn planar_new_edge_embedded() {
+    let mut mesh = HalfEdgeGraph::default();
+    let mut positions = HashMap::new();
+
+    let c = mesh.new_vertex(());
+    positions.insert(c, [0., 0.]);
+
+    let around: Vec<_> = [[1., 0.], [0., 1.], [-1., 0.], [0., -1.]]
+        .iter()
+        .map(|p| {
+            let v = mesh.new_vertex(());
+            positions.insert(v, *p);
+            v
+        })
+        .collect();
+
+    for i in &[0, 2, 1, 3] {
+        mesh.new_edge_embedded(c, around[*i], (), |v| positions[&v.handle()])
+            .expect("Failed to create edge");
+    }
+
+    // Out half edges are ordered clockwise
+    let order: Vec<_> = mesh
+        .vertex(c)
+        .unwrap()
+        .out_half_edges()
+        .map(|h| h.vertex().handle())
+        .collect();
+    let start = order.iter().position(|v| *v == around[0]).unwrap();
+    let order: Vec<_> = order.iter().cycle().skip(start).take(4).copied().collect();
+    assert_eq!(order, vec![around[0], around[3], around[2], around[1]]);
+
+    // Faces can be created in all quadrants
+    for i in 0..4 {
+        mesh.new_face(&[c, around[i], around[(i + 1) % 4]], ())
+            .expect("Failed to create face");
+    }
+
+    // Edges can't be inserted inside faces
+    let v = mesh.new_vertex(());
+    positions.insert(v, [0.25, 0.25]);
+    assert!(mesh
+        .new_edge_embedded(c, v, (), |v| positions[&v.handle()])
+        .is_none());
+
+    // Positions that aren't finite are rejected
+    positions.insert(v, [f64::NAN, f64::INFINITY]);
+    let w = mesh.new_vertex(());
+    positions.insert(w, [5., 5.]);
+    assert!(mesh
+        .new_edge_embedded(w, v, (), |v| positions[&v.handle()])
+        .is_none());
+    // Also when it is the position of a neighbor
+    let x = mesh.new_vertex(());
+    positions.insert(x, [5., 0.]);
+    mesh.new_edge(x, v, ()).unwrap();
+    assert!(mesh
+        .new_edge_embedded(x, w, (), |v| positions[&v.handle()])
+        .is_none());
+
+    // Self loops and parallel edges are never embedded, even in a multigraph
+    #[derive(Default)]
+    struct Multigraph;
+    impl crate::Data for Multigraph {
+        type Face = ();
+        type Edge = ();
+        type HalfEdge = ();
+        type Vertex = [f64; 2];
+
+        const ALLOW_PARALLEL_EDGES: bool = true;
+        const ALLOW_SELF_LOOPS: bool = true;
+    }
+
+    let mut mesh = super::HalfEdgeGraph::<Multigraph>::default();
+    let a = mesh.new_vertex([0., 0.]);
+    let b = mesh.new_vertex([1., 0.]);
+    let position = |v: crate::VertexFn<'_, Multigraph>| *v;
+    assert!(mesh.new_edge_embedded(a, a, (), position).is_none());
+    mesh.new_edge_embedded(a, b, (), position).unwrap();
+    assert!(mesh.new_edge_embedded(b, a, (), position).is_none());
+    assert_eq!(mesh.iter_edges().count(), 1);
+}
+
+fn graph_from_edges(vertex_count: usize, edges: &[(usize, usize)]) -> HalfEdgeGraph {
+    let mut mesh = HalfEdgeGraph::default();
+    let vertices: Vec<_> = (0..vertex_count).map(|_| mesh.new_vertex(())).collect();
+    for (v1, v2) in edges {
+        mesh.new_edge(vertices[*v1], vertices[*v2], ())
+            .expect("Failed to create edge");
+    }
+    mesh
+}
+
+#[test]
+fn planarity_embedding() {
+    // wheel with 6 spokes
+    let mut edges: Vec<_> = (1..7).map(|i| (0, i)).collect();
+    edges.extend((1..7).map(|i| (i, i % 6 + 1)));
+    // 3x3 grid with diagonals, connected to the wheel
+    for y in 0..3 {
+        for x in 0..3 {
+            let v = 7 + y * 3 + x;
+            if x < 2 {
+                edges.push((v, v + 1));
+            }
+            if y < 2 {
+                edges.push((v, v + 3));
+            }
+            if x < 2 && y < 2 {
+                edges.push((v, v + 4));
+            }
+        }
+    }
+    edges.push((1, 7));
+
+    let mut mesh = graph_from_edges(16, &edges);
+    assert!(mesh.is_planar());
+
+    let faces = mesh.planar_embedding().expect("Graph is planar");
+    // Euler's formula for a connected planar graph
+    assert_eq!(16 - edges.len() as isize + faces.len() as isize, 2);
+
+    assert_eq!(
+        mesh.planar_embedding(),
+        Err(crate::EmbeddingError::HasFaces)
+    );
+}
+
+#[test]
+fn planarity_long_path() {
+    // A long cycle, with a K5 at the end. Run on a small stack,
+    // the dfs must not recurse once per vertex.
+    const N: usize = 1000;
+    let mut edges: Vec<_> = (0..N).map(|i| (i, (i + 1) % N)).collect();
+    let mut mesh = graph_from_edges(N, &edges);
+    edges.extend((N - 5..N).flat_map(|i| (i + 2..N).map(move |j| (i, j))));
+    let mut non_planar = graph_from_edges(N, &edges);
+
+    std::thread::Builder::new()
+        .stack_size(32 * 1024)
+        .spawn(move || {
+            assert!(mesh.is_planar());
+            assert_eq!(mesh.planar_embedding().map(|faces| faces.len()), Ok(2));
+
+            assert!(!non_planar.is_planar());
+            match non_planar.planar_embedding() {
+                Err(crate::EmbeddingError::NonPlanar(witness)) => assert_eq!(witness.len(), 10),
+                result => panic!("Expected non planar graph, got {:?}", result),
+            }
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn planarity_kuratowski() {
+    let k5: Vec<_> = (0..5)
+        .flat_map(|i| (i + 1..5).map(move |j| (i, j)))
+        .collect();
+    let k33: Vec<_> = (0..3).flat_map(|i| (3..6).map(move |j| (i, j))).collect();
+    let petersen: Vec<_> = (0..5)
+        .flat_map(|i| vec![(i, (i + 1) % 5), (i, i + 5), (i + 5, (i + 2) % 5 + 5)])
+        .collect();
+
+    for (vertex_count, edges) in &[(5, k5), (6, k33), (10, petersen)] {
+        let mut mesh = graph_from_edges(*vertex_count, edges);
+        assert!(!mesh.is_planar());
+
+        let witness = match mesh.planar_embedding() {
+            Err(crate::EmbeddingError::NonPlanar(witness)) => witness,
+            result => panic!("Expected non planar graph, got {:?}", result),
+        };
+        assert_eq!(mesh.iter_faces().count(), 0);
+
+        // The witness is non planar, but removing any edge makes it planar
+        let lookup: HashMap<EdgeHandle, (usize, usize)> = mesh
+            .iter_edges()
+            .map(|e| e.handle())
+            .zip(edges.iter().copied())
+            .collect();
+        let subgraph: Vec<_> = witness.iter().map(|e| lookup[e]).collect();
+        assert!(!graph_from_edges(*vertex_count, &subgraph).is_planar());
+        for i in 0..subgraph.len() {
+            let mut smaller = subgraph.clone();
+            smaller.remove(i);
+            assert!(graph_from_edges(*vertex_count, &smaller).is_planar());
+        }
+    }
+}
+
+#[test]
+fn journal_undo_redo() {
+    #[derive(Default)]
+    struct Data;
+    impl crate::Data for Data {
+        type Face = ();
+        type Edge = ();
+        type HalfEdge = ();
+        type Vertex = i32;
+    }
+
+    fn links(mesh: &super::HalfEdgeGraph<Data>) -> Vec<String> {
+        mesh.iter_half_edges()
+            .map(|h| {
+                format!(
+                    "{:?} {:?} {:?} {:?} {:?} {:?}",
+                    h.handle(),
+                    h.next().handle(),
+                    h.prev().handle(),
+                    h.vertex().handle(),
+                    h.edge().handle(),
+                    h.face().map(|f| f.handle())
+                )
+            })
+            .chain(
+                mesh.iter_vertices()
+                    .map(|v| format!("{:?} {}", v.handle(), *v)),
+            )
+            .collect()
+    }
+
+    let mut mesh = super::HalfEdgeGraph::<Data>::default();
+    mesh.enable_journal();
+
+    let v: Vec<_> = mesh.transaction("triangle", |mesh| {
+        let v: Vec<_> = (0..4).map(|i| mesh.new_vertex(i)).collect();
+        mesh.new_face(&[v[0], v[1], v[2]], ()).unwrap();
+        v
+    });
+    let triangle = links(&mesh);
+
+    mesh.begin_transaction("quad");
+    mesh.new_face(&[v[0], v[2], v[3]], ()).unwrap();
+    *mesh.vertex_mut(v[0]).unwrap() = 10;
+    mesh[v[1]] = 11;
+    mesh.commit_transaction();
+    let quad = links(&mesh);
+
+    assert_eq!(mesh.undo_name(), Some("quad"));
+    assert_eq!(mesh.undo().as_deref(), Some("quad"));
+    assert_eq!(links(&mesh), triangle);
+
+    assert_eq!(mesh.undo().as_deref(), Some("triangle"));
+    assert_eq!(mesh.iter_vertices().count(), 0);
+    assert_eq!(mesh.iter_half_edges().count(), 0);
+    assert_eq!(mesh.undo(), None);
+
+    assert_eq!(mesh.redo().as_deref(), Some("triangle"));
+    assert_eq!(mesh.redo().as_deref(), Some("quad"));
+    assert_eq!(mesh.redo(), None);
+
+    // The vertices have been recreated with the same handles
+    assert_eq!(links(&mesh), quad);
+    assert_eq!(mesh[v[0]], 10);
+    assert_eq!(mesh[v[1]], 11);
+    assert!(mesh.find_edge(v[0], v[3]).is_some());
+
+    // Mutations outside of a transaction are grouped
+    let quad = links(&mesh);
+    let v4 = mesh.new_vertex(4);
+    mesh.new_face(&[v[0], v[3], v4], ()).unwrap();
+    mesh[v[3]] = 13;
+    assert_eq!(mesh.undo_name(), Some(""));
+    mesh.undo();
+    assert_eq!(links(&mesh), quad);
+
+    // New mutations clear the redo history
+    mesh[v[2]] = 12;
+    assert_eq!(mesh.redo(), None);
+}
+
+#[test]
+fn graph_events() {
+    use crate::{GraphEvent, Handle};
+
+    let mut mesh = HalfEdgeGraph::default();
+    let v1 = mesh.new_vertex(());
+    mesh.enable_events();
+
+    let v2 = mesh.new_vertex(());
+    let v3 = mesh.new_vertex(());
+    let e = mesh.new_edge(v1, v2, ()).unwrap();
+    assert_eq!(
+        mesh.drain_events().collect::<Vec<_>>(),
+        vec![
+            GraphEvent::VertexAdded(v2),
+            GraphEvent::VertexAdded(v3),
+            GraphEvent::EdgeAdded(e)
+        ]
+    );
+    assert_eq!(mesh.drain_events().count(), 0);
+
+    // The existing half edges are relinked when the face is created
+    let f = mesh.new_face(&[v1, v2, v3], ()).unwrap();
+    let events: HashSet<_> = mesh.drain_events().collect();
+    assert!(events.contains(&GraphEvent::FaceAdded(f)));
+    let e_hedge = mesh.edge(e).unwrap().hedge().handle();
+    assert!(events.contains(&GraphEvent::HalfEdgeRelinked(e_hedge)));
+
+    mesh[v1] = ();
+    *mesh.face_mut(f).unwrap() = ();
+    assert_eq!(
+        mesh.drain_events().collect::<Vec<_>>(),
+        vec![
+            GraphEvent::DataModified(Handle::Vertex(v1)),
+            GraphEvent::DataModified(Handle::Face(f))
+        ]
+    );
+
+    // Undo reports the removed items
+    mesh.enable_journal();
+    let v4 = mesh.new_vertex(());
+    mesh.undo();
+    assert_eq!(
+        mesh.drain_events().collect::<Vec<_>>(),
+        vec![GraphEvent::VertexAdded(v4), GraphEvent::VertexRemoved(v4)]
+    );
+
+    mesh.disable_events();
+    mesh.new_vertex(());
+    assert_eq!(mesh.drain_events().count(), 0);
+}
+
+#[test]
+fn clone_and_isomorphism() {
+    fn build(order: &[usize]) -> (HalfEdgeGraph, Vec<VertexHandle>) {
+        let mut mesh = HalfEdgeGraph::default();
+        let mut v = vec![VertexHandle::null(); order.len()];
+        for i in order {
+            v[*i] = mesh.new_vertex(());
+        }
+        // A quad of 2 triangles, a separate edge and a isolated vertex
+        mesh.new_face(&[v[0], v[1], v[2]], ()).unwrap();
+        mesh.new_face(&[v[0], v[2], v[3]], ()).unwrap();
+        mesh.new_edge(v[4], v[5], ()).unwrap();
+        (mesh, v)
+    }
+
+    let (a, va) = build(&[0, 1, 2, 3, 4, 5, 6]);
+    let (b, vb) = build(&[6, 5, 4, 3, 2, 1, 0]);
+
+    let copy = a.clone();
+    assert!(copy == a);
+    assert!(a != b);
+
+    let map = a.isomorphism(&b).expect("Graphs should be isomorphic");
+    // The diagonal is the only edge between vertices of degree 3
+    let diagonal = map.edge(a.find_edge(va[0], va[2]).unwrap()).unwrap();
+    assert_eq!(Some(diagonal), b.find_edge(vb[0], vb[2]));
+    assert_eq!(map.vertex(va[6]), Some(vb[6]));
+    for face in a.iter_faces() {
+        assert!(map.face(face.handle()).is_some());
+    }
+
+    // Same number of items, but different structure
+    let mut path = HalfEdgeGraph::default();
+    let mut star = HalfEdgeGraph::default();
+    let p: Vec<_> = (0..4).map(|_| path.new_vertex(())).collect();
+    let s: Vec<_> = (0..4).map(|_| star.new_vertex(())).collect();
+    for i in 1..4 {
+        path.new_edge(p[i - 1], p[i], ()).unwrap();
+        star.new_edge(s[0], s[i], ()).unwrap();
+    }
+    assert!(path.is_isomorphic(&path.clone()));
+    assert!(!path.is_isomorphic(&star));
+
+    let mut copy = copy;
+    copy.new_edge(va[1], va[3], ()).unwrap();
+    assert!(copy != a);
+    assert!(!copy.is_isomorphic(&a));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn rayon_parallel_iteration() {
+    use rayon::prelude::*;
+
+    #[derive(Default)]
+    struct Data;
+    impl crate::Data for Data {
+        type Face = usize;
+        type Edge = ();
+        type HalfEdge = ();
+        type Vertex = usize;
+    }
+
+    let mut mesh = super::HalfEdgeGraph::<Data>::default();
+    let v: Vec<_> = (0..100).map(|i| mesh.new_vertex(i)).collect();
+    for y in 0..9 {
+        for x in 0..9 {
+            let i = y * 10 + x;
+            mesh.new_face(&[v[i], v[i + 1], v[i + 11], v[i + 10]], 0)
+                .unwrap();
+        }
+    }
+
+    assert_eq!(mesh.par_iter_vertices().map(|v| *v).sum::<usize>(), 4950);
+    assert_eq!(
+        mesh.par_iter_faces()
+            .map(|f| f.vertices().count())
+            .sum::<usize>(),
+        81 * 4
+    );
+    assert_eq!(mesh.par_iter_edges().count(), mesh.iter_edges().count());
+    assert_eq!(
+        mesh.par_iter_half_edges().count(),
+        mesh.iter_half_edges().count()
+    );
+
+    mesh.enable_journal();
+    mesh.par_for_each_vertex_data_mut(|_, data| *data *= 2);
+    mesh.par_for_each_face_data_mut(|_, data| *data = 1);
+    assert_eq!(mesh[v[99]], 198);
+    assert_eq!(mesh.iter_faces().map(|f| *f).sum::<usize>(), 81);
+
+    mesh.undo();
+    assert_eq!(mesh[v[99]], 99);
+    assert_eq!(mesh.iter_faces().map(|f| *f).sum::<usize>(), 0);
+}
+
+#[test]
+fn disjoint_mutable_access() {
+    #[derive(Default)]
+    struct Data;
+    impl crate::Data for Data {
+        type Face = ();
+        type Edge = f64;
+        type HalfEdge = ();
+        type Vertex = f64;
+    }
+
+    let mut mesh = super::HalfEdgeGraph::<Data>::default();
+    let v1 = mesh.new_vertex(0.);
+    let v2 = mesh.new_vertex(10.);
+    let e = mesh.new_edge(v1, v2, 0.5).unwrap();
+
+    // Pull both endpoints towards each other
+    let mut edge = mesh.edge_mut(e).unwrap();
+    let strength = *edge;
+    let [a, b] = edge.vertices_data_mut().unwrap();
+    let delta = (*b - *a) * strength / 2.;
+    *a += delta;
+    *b -= delta;
+    assert_eq!(mesh[v1], 2.5);
+    assert_eq!(mesh[v2], 7.5);
+
+    let [a, b] = mesh.get_disjoint_vertices_mut([v2, v1]).unwrap();
+    std::mem::swap(a, b);
+    assert_eq!(mesh[v1], 7.5);
+
+    assert!(mesh.get_disjoint_vertices_mut([v1, v1]).is_none());
+    assert!(mesh
+        .get_disjoint_vertices_mut([v1, VertexHandle::null()])
+        .is_none());
+    assert!(mesh.get_disjoint_edges_mut([e]).is_some());
+    assert!(mesh.get_disjoint_faces_mut([]).is_some());
+
+    // Both ends of a self loop are the same vertex
+    #[derive(Default)]
+    struct Loops;
+    impl crate::Data for Loops {
+        type Face = ();
+        type Edge = ();
+        type HalfEdge = ();
+        type Vertex = f64;
+
+        const ALLOW_SELF_LOOPS: bool = true;
+    }
+
+    let mut mesh = super::HalfEdgeGraph::<Loops>::default();
+    let v = mesh.new_vertex(0.);
+    let e = mesh.new_edge(v, v, ()).unwrap();
+    assert!(mesh.edge_mut(e).unwrap().vertices_data_mut().is_none());
+}
+
+#[test]
+fn mutable_visitors() {
+    #[derive(Default)]
+    struct Data;
+    impl crate::Data for Data {
+        type Face = ();
+        type Edge = u32;
+        type HalfEdge = ();
+        type Vertex = u32;
+    }
+
+    let mut mesh = super::HalfEdgeGraph::<Data>::default();
+    let c = mesh.new_vertex(0);
+    let v: Vec<_> = (1..=4).map(|i| mesh.new_vertex(i)).collect();
+    for i in 0..4 {
+        mesh.new_face(&[c, v[i], v[(i + 1) % 4]], ()).unwrap();
+    }
+
+    let mut center = mesh.vertex_mut(c).unwrap();
+    center.vertices_mut().for_each(|mut vertex| *vertex *= 10);
+    center.edges_mut().for_each(|mut edge| *edge = 1);
+    let sum = center.vertices_mut().fold(0, |sum, vertex| sum + *vertex);
+    assert_eq!(sum, 100);
+
+    let handles: HashSet<_> = center.vertices_mut().map_collect(|vertex| vertex.handle());
+    assert_eq!(handles, v.iter().copied().collect());
+
+    // Stops at the first error
+    let mut visited = 0;
+    let result = center.vertices_mut().try_for_each(|vertex| {
+        visited += 1;
+        if *vertex == 30 {
+            Err(vertex.handle())
+        } else {
+            Ok(())
+        }
+    });
+    assert_eq!(result, Err(v[2]));
+    assert!(visited <= 4);
+
+    let mut face = mesh
+        .vertex_mut(c)
+        .unwrap()
+        .as_hedge()
+        .unwrap()
+        .as_face()
+        .unwrap();
+    let edges: u32 = face.edges_mut().fold(0, |sum, edge| sum + *edge);
+    assert_eq!(edges, 2);
+}
+
+#[test]
+fn neighborhood_updates() {
+    #[derive(Default)]
+    struct Data;
+    impl crate::Data for Data {
+        type Face = usize;
+        type Edge = usize;
+        type HalfEdge = ();
+        type Vertex = f64;
+    }
+
+    fn average(center: &mut f64, neighbors: crate::NeighborView<crate::VertexFn<'_, Data>>) {
+        *center = neighbors.iter().sum::<f64>() / neighbors.len() as f64;
+    }
+
+    let build = || {
+        let mut mesh = super::HalfEdgeGraph::<Data>::default();
+        let v: Vec<_> = [0., 0., 10., 0., 0.]
+            .iter()
+            .map(|value| mesh.new_vertex(*value))
+            .collect();
+        for i in 1..v.len() {
+            mesh.new_edge(v[i - 1], v[i], 0).unwrap();
+        }
+        (mesh, v)
+    };
+
+    let (mut mesh, v) = build();
+    mesh.update_vertices(average);
+    let values: Vec<_> = v.iter().map(|v| mesh[*v]).collect();
+    assert_eq!(values, vec![0., 5., 0., 5., 0.]);
+
+    let (mut mesh, v) = build();
+    mesh.update_vertices_in_place(average);
+    let values: Vec<_> = v.iter().map(|v| mesh[*v]).collect();
+    assert_eq!(values, vec![0., 5., 2.5, 1.25, 1.25]);
+
+    mesh.update_edges(|center, neighbors| *center = neighbors.len());
+    let edges: Vec<_> = mesh.iter_edges().map(|e| *e).collect();
+    assert_eq!(edges, vec![1, 2, 2, 1]);
+
+    // Each face in a row of 3 quads adds the values of its neighbors to its own
+    let mut mesh = super::HalfEdgeGraph::<Data>::default();
+    let v: Vec<_> = (0..8).map(|_| mesh.new_vertex(0.)).collect();
+    for i in 0..3 {
+        mesh.new_face(&[v[i], v[i + 1], v[i + 5], v[i + 4]], 1)
+            .unwrap();
+    }
+    mesh.update_faces(|center, neighbors| *center += neighbors.iter().sum::<usize>());
+    let faces: Vec<_> = mesh.iter_faces().map(|f| *f).collect();
+    assert_eq!(faces, vec![2, 3, 2]);
+
+    // A vertex with a self loop is its own neighbor (twice), and reads the default value when
+    // updated in place
+    #[derive(Default)]
+    struct Loops;
+    impl crate::Data for Loops {
+        type Face = ();
+        type Edge = ();
+        type HalfEdge = ();
+        type Vertex = f64;
+
+        const ALLOW_SELF_LOOPS: bool = true;
+    }
+
+    let build = || {
+        let mut mesh = super::HalfEdgeGraph::<Loops>::default();
+        let v1 = mesh.new_vertex(4.);
+        let v2 = mesh.new_vertex(2.);
+        mesh.new_edge(v1, v1, ()).unwrap();
+        mesh.new_edge(v1, v2, ()).unwrap();
+        (mesh, v1)
+    };
+    let sum = |center: &mut f64, neighbors: crate::NeighborView<crate::VertexFn<'_, Loops>>| {
+        *center = neighbors.iter().sum::<f64>()
+    };
+
+    let (mut mesh, v1) = build();
+    mesh.update_vertices(sum);
+    assert_eq!(mesh[v1], 10.);
+
+    let (mut mesh, v1) = build();
+    mesh.update_vertices_in_place(sum);
+    assert_eq!(mesh[v1], 2.);
+}
+
+#[test]
+fn multigraph_edges() {
+    #[derive(Default)]
+    struct Data;
+    impl crate::Data for Data {
+        type Face = ();
+        type Edge = ();
+        type HalfEdge = ();
+        type Vertex = ();
+
+        const ALLOW_PARALLEL_EDGES: bool = true;
+        const ALLOW_SELF_LOOPS: bool = true;
+    }
+
+    // Simple graphs still rejects them
+    let mut simple = HalfEdgeGraph::default();
+    let v = simple.new_vertex(());
+    let u = simple.new_vertex(());
+    assert!(simple.new_edge(v, v, ()).is_none());
+    simple.new_edge(v, u, ()).unwrap();
+    assert!(simple.new_edge(u, v, ()).is_none());
+
+    let mut mesh = super::HalfEdgeGraph::<Data>::default();
+    let v1 = mesh.new_vertex(());
+    let v2 = mesh.new_vertex(());
+    let v3 = mesh.new_vertex(());
+
+    // A loop on a vertex without edges, and on a vertex with edges
+    let l1 = mesh.new_edge(v1, v1, ()).unwrap();
+    let e1 = mesh.new_edge(v2, v3, ()).unwrap();
+    let l2 = mesh.new_edge(v2, v2, ()).unwrap();
+    let l3 = mesh.new_edge(v2, v2, ()).unwrap();
+
+    let e2 = mesh.new_edge(v1, v2, ()).unwrap();
+    let e3 = mesh.new_edge(v2, v1, ()).unwrap();
+    let e4 = mesh.new_edge(v1, v2, ()).unwrap();
+
+    let edges: HashSet<_> = mesh.find_edges(v1, v2).collect();
+    assert_eq!(edges, [e2, e3, e4].iter().copied().collect());
+    let loops: HashSet<_> = mesh.find_edges(v2, v2).collect();
+    assert_eq!(loops, [l2, l3].iter().copied().collect());
+    assert_eq!(mesh.find_edges(v1, v1).collect::<Vec<_>>(), vec![l1]);
+    assert_eq!(mesh.find_edges(v3, v2).collect::<Vec<_>>(), vec![e1]);
+
+    // Loops are part of the fan twice, once for each half edge
+    assert_eq!(mesh.vertex(v2).unwrap().edges().count(), 8);
+    assert_eq!(mesh.vertex(v1).unwrap().out_half_edges().count(), 5);
+
+    // A face between 2 of the parallel edges
+    let h2 = mesh.edge(e2).unwrap().hedge().handle();
+    let h4 = mesh.edge(e4).unwrap().hedge().pair().handle();
+    let face = mesh.new_face_from_half_edges(&[h2, h4], ()).unwrap();
+    assert_eq!(mesh.face(face).unwrap().edges().count(), 2);
+
+    // A face inside a loop
+    let loop_hedge = mesh.edge(l1).unwrap().hedge().handle();
+    let face = mesh.new_face_from_half_edges(&[loop_hedge], ()).unwrap();
+    assert_eq!(mesh.face(face).unwrap().vertices().count(), 1);
+
+    // Not a chain
+    let h3 = mesh.find_half_edge(v2, v3).unwrap();
+    let free = mesh.edge(e3).unwrap().hedge().pair().handle();
+    assert_eq!(
+        mesh.new_face_from_half_edges(&[free, h3], ()),
+        Err(crate::FaceError::NotChained {
+            hedge: h3,
+            next: free
+        })
+    );
+    assert_eq!(
+        mesh.new_face_from_half_edges(&[h2, h4], ()),
+        Err(crate::FaceError::HasFace(h2))
+    );
+    let h5 = mesh.find_half_edge(v3, v2).unwrap();
+    assert_eq!(
+        mesh.new_face_from_half_edges(&[h3, h5, h3], ()),
+        Err(crate::FaceError::Duplicate(h3))
+    );
+    assert_eq!(
+        mesh.new_face_from_half_edges(&[], ()),
+        Err(crate::FaceError::Empty)
+    );
+
+    assert!(mesh.is_planar());
+}
+
+#[test]
+fn face_from_half_edges_not_adjacent() {
+    // 3 triangles touching at `c`, with a gap without faces between each of them
+    let mut mesh = HalfEdgeGraph::default();
+    let c = mesh.new_vertex(());
+    let v: Vec<_> = (0..6).map(|_| mesh.new_vertex(())).collect();
+    for i in 0..3 {
+        mesh.new_face(&[c, v[2 * i], v[2 * i + 1]], ()).unwrap();
+    }
+
+    // The free half edges around `c` pairs up as (v0, v5), (v4, v3) & (v2, v1)
+    let order: Vec<_> = mesh
+        .vertex(c)
+        .unwrap()
+        .out_half_edges()
+        .map(|h| h.vertex().handle())
+        .collect();
+    let start = order.iter().position(|h| *h == v[0]).unwrap();
+    let order: Vec<_> = order.iter().cycle().skip(start).take(6).copied().collect();
+    assert_eq!(order, vec![v[0], v[5], v[4], v[3], v[2], v[1]]);
+
+    // `x` has a free edge, so the half edges around it can be moved
+    let x = mesh.new_vertex(());
+    let y = mesh.new_vertex(());
+    mesh.new_edge(x, v[1], ()).unwrap();
+    mesh.new_edge(x, y, ()).unwrap();
+    mesh.new_edge(x, v[0], ()).unwrap();
+    let h1 = mesh.find_half_edge(v[1], x).unwrap();
+    let h2 = mesh.find_half_edge(x, v[0]).unwrap();
+    assert_ne!(mesh.half_edge(h1).unwrap().next().handle(), h2);
+
+    // A face through all 3 gaps pairing them differently, there is no fourth gap to
+    // move the other half edges to. The half edges around `x` are checked first.
+    let path = [v[1], x, v[0], c, v[3], v[2], c, v[5], v[4], c];
+    let hedges: Vec<_> = path
+        .iter()
+        .circular_tuple_windows()
+        .map(|(a, b)| mesh.find_half_edge(*a, *b).unwrap())
+        .collect();
+    let before = mesh.clone();
+    assert_eq!(
+        mesh.new_face_from_half_edges(&hedges, ()),
+        Err(crate::FaceError::NotAdjacent {
+            hedge: hedges[2],
+            next: hedges[3]
+        })
+    );
+
+    // The graph is left unchanged
+    assert!(mesh.validate().is_ok());
+    assert!(mesh == before);
+    assert_ne!(mesh.half_edge(h1).unwrap().next().handle(), h2);
+    let order: Vec<_> = mesh
+        .vertex(c)
+        .unwrap()
+        .out_half_edges()
+        .map(|h| h.vertex().handle())
+        .collect();
+    let start = order.iter().position(|h| *h == v[0]).unwrap();
+    let order: Vec<_> = order.iter().cycle().skip(start).take(6).copied().collect();
+    assert_eq!(order, vec![v[0], v[5], v[4], v[3], v[2], v[1]]);
+}
+
+#[test]
+fn non_manifold_vertices() {
+    let mut mesh = HalfEdgeGraph::default();
+
+    // 2 triangles touching at `c`, and a edge without faces
+    let c = mesh.new_vertex(());
+    let v: Vec<_> = (0..5).map(|_| mesh.new_vertex(())).collect();
+    let f1 = mesh.new_face(&[c, v[0], v[1]], ()).unwrap();
+    let f2 = mesh.new_face(&[c, v[2], v[3]], ()).unwrap();
+    mesh.new_edge(c, v[4], ()).unwrap();
+
+    assert_eq!(mesh.non_manifold_vertices(), vec![c]);
+    let before = mesh.clone();
+
+    mesh.enable_journal();
+    let mapping = mesh.transaction("split", |mesh| mesh.split_non_manifold_vertices());
+
+    assert_eq!(mapping.len(), 1);
+    let (&split, &original) = mapping.iter().next().unwrap();
+    assert_eq!(original, c);
+
+    assert!(mesh.non_manifold_vertices().is_empty());
+    assert_eq!(mesh.iter_vertices().count(), 7);
+    assert_eq!(mesh.vertex(c).unwrap().edges().count(), 3);
+    assert_eq!(mesh.vertex(split).unwrap().edges().count(), 2);
+
+    let f1: HashSet<_> = mesh
+        .face(f1)
+        .unwrap()
+        .vertices()
+        .map(|v| v.handle())
+        .collect();
+    let f2: HashSet<_> = mesh
+        .face(f2)
+        .unwrap()
+        .vertices()
+        .map(|v| v.handle())
+        .collect();
+    assert_eq!(f1.len(), 3);
+    assert_eq!(f2.len(), 3);
+    assert!(f1.is_disjoint(&f2));
+
+    mesh.undo();
+    assert_eq!(mesh, before);
+    assert_eq!(mesh.non_manifold_vertices(), vec![c]);
+}
+
+#[test]
+fn glued_polygons() {
+    let mut mesh = HalfEdgeGraph::default();
+    let v: Vec<_> = (0..5).map(|_| mesh.new_vertex(())).collect();
+
+    // 3 triangles sharing the edge v0-v1
+    let import = mesh.add_polygons(&[
+        vec![v[0], v[1], v[2]],
+        vec![v[1], v[0], v[3]],
+        vec![v[0], v[1], v[4]],
+    ]);
+    assert!(import.faces.iter().all(|face| face.is_ok()));
+    assert!(!import.is_manifold());
+
+    assert_eq!(import.glued.len(), 1);
+    let glued = &import.glued[0];
+    assert_eq!(glued.vertices, [v[0], v[1]]);
+    assert_eq!(glued.edges.len(), 2);
+    let edges: HashSet<_> = mesh.find_edges(v[0], v[1]).collect();
+    assert_eq!(edges, glued.edges.iter().copied().collect());
+
+    for face in &import.faces {
+        assert_eq!(
+            mesh.face(*face.as_ref().unwrap()).unwrap().edges().count(),
+            3
+        );
+    }
+
+    // A manifold strip doesn't need any glue
+    let mut mesh = HalfEdgeGraph::default();
+    let v: Vec<_> = (0..4).map(|_| mesh.new_vertex(())).collect();
+    let import = mesh.add_polygons(&[[v[0], v[1], v[2]], [v[2], v[1], v[3]]]);
+    assert!(import.is_manifold());
+    assert_eq!(mesh.iter_edges().count(), 5);
+
+    let import = mesh.add_polygons(&[vec![v[0], v[0], v[1]], vec![]]);
+    assert_eq!(
+        import.faces,
+        vec![
+            Err(crate::FaceError::SelfLoop(v[0])),
+            Err(crate::FaceError::Empty)
+        ]
+    );
+
+    // The vertices of a closed tetrahedron are surrounded by faces, so no edges can be added
+    let mut mesh = HalfEdgeGraph::default();
+    let v: Vec<_> = (0..5).map(|_| mesh.new_vertex(())).collect();
+    let import = mesh.add_polygons(&[
+        [v[0], v[1], v[2]],
+        [v[0], v[3], v[1]],
+        [v[1], v[3], v[2]],
+        [v[2], v[3], v[0]],
+    ]);
+    assert!(import.is_manifold());
+
+    let import = mesh.add_polygons(&[[v[0], v[1], v[4]], [v[4], v[0], v[2]]]);
+    assert_eq!(
+        import.faces,
+        vec![
+            Err(crate::FaceError::NoFreeHalfEdge(v[0])),
+            Err(crate::FaceError::NoFreeHalfEdge(v[0]))
+        ]
+    );
+    assert!(import.glued.is_empty());
+    assert_eq!(mesh.iter_edges().count(), 6);
+}
+
+#[test]
+fn oriented_polygons() {
+    let mut mesh = HalfEdgeGraph::default();
+    let v: Vec<_> = (0..5).map(|_| mesh.new_vertex(())).collect();
+
+    // The middle triangle have the opposite winding of its neighbors
+    let import =
+        mesh.add_oriented_polygons(&[[v[0], v[1], v[2]], [v[1], v[2], v[3]], [v[2], v[3], v[4]]]);
+    assert_eq!(import.flipped, vec![1]);
+    assert!(import.non_orientable.is_empty());
+    assert!(import.is_manifold());
+
+    // The flipped triangle goes from v2 to v1
+    let f2 = *import.faces[1].as_ref().unwrap();
+    let hedge = mesh.find_half_edge(v[2], v[1]).unwrap();
+    assert_eq!(mesh.half_edge(hedge).unwrap().face().unwrap().handle(), f2);
+
+    // A möbius strip
+    let mut mesh = HalfEdgeGraph::default();
+    let top: Vec<_> = (0..5).map(|_| mesh.new_vertex(())).collect();
+    let bottom: Vec<_> = (0..5).map(|_| mesh.new_vertex(())).collect();
+    let mut quads: Vec<_> = (0..4)
+        .map(|i| vec![top[i], top[i + 1], bottom[i + 1], bottom[i]])
+        .collect();
+    quads.push(vec![top[4], bottom[0], top[0], bottom[4]]);
+
+    let import = mesh.add_oriented_polygons(&quads);
+    assert_eq!(import.non_orientable, vec![vec![0, 1, 2, 3, 4]]);
+    assert!(import.faces.iter().all(|face| face.is_ok()));
+    assert_eq!(import.glued.len(), 1);
+}
+
+#[test]
+fn reverse_faces() {
+    fn face_vertices(mesh: &HalfEdgeGraph, face: FaceHandle) -> Vec<VertexHandle> {
+        let vertices: Vec<_> = mesh
+            .face(face)
+            .unwrap()
+            .vertices()
+            .map(|v| v.handle())
+            .collect();
+        // Rotate so the smallest vertex is first, to compare loops
+        let min = vertices.iter().position_min().unwrap();
+        vertices[min..]
+            .iter()
+            .chain(&vertices[..min])
+            .copied()
+            .collect()
+    }
+    fn reversed(vertices: &[VertexHandle]) -> Vec<VertexHandle> {
+        let mut reversed = vertices.to_vec();
+        reversed[1..].reverse();
+        reversed
+    }
+
+    let mut mesh = HalfEdgeGraph::default();
+    let v: Vec<_> = (0..6).map(|_| mesh.new_vertex(())).collect();
+    let f1 = mesh.new_face(&[v[0], v[1], v[2]], ()).unwrap();
+    let f2 = mesh.new_face(&[v[2], v[1], v[3]], ()).unwrap();
+    let f3 = mesh.new_face(&[v[3], v[4], v[5]], ()).unwrap();
+    mesh.new_edge(v[0], v[5], ()).unwrap();
+
+    // f3 only shares a vertex with the other faces
+    let before = face_vertices(&mesh, f3);
+    mesh.reverse_face(f3).unwrap();
+    assert_eq!(face_vertices(&mesh, f3), reversed(&before));
+    let hedge = mesh.find_half_edge(v[4], v[3]).unwrap();
+    assert_eq!(mesh.half_edge(hedge).unwrap().face().unwrap().handle(), f3);
+    assert!(mesh.half_edge(hedge).unwrap().pair().face().is_none());
+
+    let shared = mesh.find_half_edge(v[2], v[1]).unwrap();
+    assert_eq!(
+        mesh.reverse_face(f1),
+        Err(crate::FaceError::HasFace(shared))
+    );
+
+    // The whole component is reversed, since everything is connected by edges
+    let before: Vec<_> = [f1, f2, f3]
+        .iter()
+        .map(|face| face_vertices(&mesh, *face))
+        .collect();
+    let copy = mesh.clone();
+    mesh.enable_journal();
+    mesh.reverse_component(f1).unwrap();
+    for (face, before) in [f1, f2, f3].iter().zip(&before) {
+        assert_eq!(face_vertices(&mesh, *face), reversed(before));
+    }
+
+    mesh.undo();
+    assert_eq!(mesh, copy);
+}
+
+#[test]
+fn ply_read_write() {
+    use crate::io::ply::{self, Format, PlyData, Properties, Property, ScalarType, Value};
+
+    #[derive(Default, Debug)]
+    struct Data;
+    impl crate::Data for Data {
+        type Face = [u8; 3];
+        type Edge = f32;
+        type HalfEdge = ();
+        type Vertex = (f32, i32);
+    }
+    impl PlyData for Data {
+        fn vertex_properties() -> Vec<Property> {
+            vec![
+                Property::scalar("x", ScalarType::Float),
+                Property::scalar("quality", ScalarType::Int),
+            ]
+        }
+        fn read_vertex(properties: &Properties) -> (f32, i32) {
+            (
+                properties.f64("x").unwrap() as f32,
+                properties.i64("quality").unwrap_or(-1) as i32,
+            )
+        }
+        fn write_vertex(vertex: &(f32, i32)) -> Vec<Value> {
+            vec![Value::Float(vertex.0 as f64), Value::Int(vertex.1 as i64)]
+        }
+
+        fn face_properties() -> Vec<Property> {
+            ["red", "green", "blue"]
+                .iter()
+                .map(|name| Property::scalar(*name, ScalarType::UChar))
+                .collect()
+        }
+        fn read_face(properties: &Properties) -> [u8; 3] {
+            let get = |name| properties.i64(name).unwrap_or(0) as u8;
+            [get("red"), get("green"), get("blue")]
+        }
+        fn write_face(face: &[u8; 3]) -> Vec<Value> {
+            face.iter().map(|c| Value::Int(*c as i64)).collect()
+        }
+
+        fn edge_properties() -> Vec<Property> {
+            vec![Property::scalar("weight", ScalarType::Double)]
+        }
+        fn read_edge(properties: &Properties) -> f32 {
+            properties.f64("weight").unwrap() as f32
+        }
+        fn write_edge(edge: &f32) -> Vec<Value> {
+            vec![Value::Float(*edge as f64)]
+        }
+    }
+
+    let mut mesh = super::HalfEdgeGraph::<Data>::default();
+    let v: Vec<_> = (0..5)
+        .map(|i| mesh.new_vertex((i as f32, i * 10)))
+        .collect();
+    mesh.new_face(&[v[0], v[1], v[2], v[3]], [255, 0, 0])
+        .unwrap();
+    mesh.new_face(&[v[1], v[0], v[4]], [0, 128, 255]).unwrap();
+    let edges: Vec<_> = mesh.iter_edges().map(|e| e.handle()).collect();
+    for edge in edges {
+        let [v1, v2] = mesh.edge(edge).unwrap().vertices();
+        mesh[edge] = v1.0 + v2.0;
+    }
+
+    for &format in &[
+        Format::Ascii,
+        Format::BinaryLittleEndian,
+        Format::BinaryBigEndian,
+    ] {
+        let mut file = Vec::new();
+        ply::write(&mesh, &mut file, format).unwrap();
+        let read: super::HalfEdgeGraph<Data> = ply::read(&file[..]).unwrap();
+
+        let map = read.isomorphism(&mesh).expect("Graph changed");
+        for vertex in read.iter_vertices() {
+            assert_eq!(*vertex, mesh[map.vertex(vertex.handle()).unwrap()]);
+        }
+        for edge in read.iter_edges() {
+            assert_eq!(*edge, mesh[map.edge(edge.handle()).unwrap()]);
+        }
+        for face in read.iter_faces() {
+            assert_eq!(*face, mesh[map.face(face.handle()).unwrap()]);
+        }
+    }
+
+    let file = "ply
+format ascii 1.0
+comment made by hand
+element vertex 3
+property float x
+element camera 1
+property float focus
+element face 1
+property list uchar uint vertex_index
+end_header
+0.5
+1
+2
+3.5
+3 2 1 0
+";
+    let read: super::HalfEdgeGraph<Data> = ply::read(file.as_bytes()).unwrap();
+    assert_eq!(read.iter_faces().count(), 1);
+    assert_eq!(read.iter_edges().count(), 3);
+    let vertices: Vec<_> = read.iter_vertices().map(|v| *v).collect();
+    assert_eq!(vertices, vec![(0.5, -1), (1., -1), (2., -1)]);
+
+    let invalid = file.replace("3 2 1 0", "3 2 1 7");
+    assert!(ply::read::<Data, _>(invalid.as_bytes()).is_err());
+
+    // A huge count in the header fails when the elements run out
+    let huge = file.replace("element face 1", &format!("element face {}", usize::MAX));
+    assert!(matches!(
+        ply::read::<Data, _>(huge.as_bytes()),
+        Err(crate::io::ReadError::Parse(_))
+    ));
+
+    // Parallel edges & self loops keep their own rows
+    #[derive(Default, Debug)]
+    struct Multigraph;
+    impl crate::Data for Multigraph {
+        type Face = ();
+        type Edge = f32;
+        type HalfEdge = ();
+        type Vertex = f32;
+        const ALLOW_PARALLEL_EDGES: bool = true;
+        const ALLOW_SELF_LOOPS: bool = true;
+    }
+    impl PlyData for Multigraph {
+        fn vertex_properties() -> Vec<Property> {
+            vec![Property::scalar("x", ScalarType::Float)]
+        }
+        fn read_vertex(properties: &Properties) -> f32 {
+            properties.f64("x").unwrap() as f32
+        }
+        fn write_vertex(vertex: &f32) -> Vec<Value> {
+            vec![Value::Float(*vertex as f64)]
+        }
+        fn edge_properties() -> Vec<Property> {
+            Data::edge_properties()
+        }
+        fn read_edge(properties: &Properties) -> f32 {
+            Data::read_edge(properties)
+        }
+        fn write_edge(edge: &f32) -> Vec<Value> {
+            Data::write_edge(edge)
+        }
+    }
+    fn edge_rows(graph: &super::HalfEdgeGraph<Multigraph>) -> Vec<(f32, f32, f32)> {
+        let mut rows: Vec<_> = graph
+            .iter_edges()
+            .map(|edge| {
+                let [v1, v2] = edge.vertices();
+                (v1.min(*v2), v1.max(*v2), *edge)
+            })
+            .collect();
+        rows.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        rows
+    }
+
+    let mut graph = super::HalfEdgeGraph::<Multigraph>::default();
+    let v: Vec<_> = (0..3).map(|i| graph.new_vertex(i as f32)).collect();
+    graph.new_face(&[v[0], v[1], v[2]], ()).unwrap();
+    let parallel = graph.new_edge(v[0], v[1], 0.).unwrap();
+    let parallel2 = graph.new_edge(v[1], v[0], 0.).unwrap();
+    let self_loop = graph.new_edge(v[2], v[2], 0.).unwrap();
+    let edges: Vec<_> = graph.iter_edges().map(|e| e.handle()).collect();
+    for (i, edge) in edges.into_iter().enumerate() {
+        graph[edge] = i as f32 + 0.5;
+    }
+    assert!(graph[parallel] != graph[parallel2] && graph[parallel] != graph[self_loop]);
+
+    let mut file = Vec::new();
+    ply::write(&graph, &mut file, Format::Ascii).unwrap();
+    let read: super::HalfEdgeGraph<Multigraph> = ply::read(&file[..]).unwrap();
+    assert_eq!(read.iter_edges().count(), 6);
+    assert_eq!(edge_rows(&read), edge_rows(&graph));
+    read.verify_invarians();
+}
+
+#[test]
+fn off_and_stl() {
+    use crate::io::{off, stl};
+
+    #[derive(Default, Debug)]
+    struct Data;
+    impl crate::Data for Data {
+        type Face = ();
+        type Edge = ();
+        type HalfEdge = ();
+        type Vertex = [f64; 3];
+    }
+
+    // A cube
+    let mut mesh = super::HalfEdgeGraph::<Data>::default();
+    let v: Vec<_> = (0..8)
+        .map(|i| mesh.new_vertex([(i & 1) as f64, (i >> 1 & 1) as f64, (i >> 2) as f64]))
+        .collect();
+    for quad in &[
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+    ] {
+        mesh.new_face(&quad.map(|i| v[i]), ()).unwrap();
+    }
+
+    let mut file = Vec::new();
+    off::write(&mesh, &mut file).unwrap();
+    let read: super::HalfEdgeGraph<Data> = off::read(&file[..]).unwrap();
+    assert!(read.is_isomorphic(&mesh));
+
+    for &format in &[stl::Format::Ascii, stl::Format::Binary] {
+        let mut file = Vec::new();
+        stl::write(&mesh, &mut file, format).unwrap();
+        let read: super::HalfEdgeGraph<Data> = stl::read(&file[..], stl::Welding::Exact).unwrap();
+
+        // The quads are split into triangles
+        assert_eq!(read.iter_vertices().count(), 8);
+        assert_eq!(read.iter_faces().count(), 12);
+        assert_eq!(read.iter_edges().count(), 18);
+        assert!(read.iter_half_edges().all(|hedge| hedge.face().is_some()));
+    }
+
+    // 2 triangles where the shared vertices are slightly off
+    let file = "solid test
+facet normal 0 0 1
+outer loop
+vertex 0 0 0
+vertex 1 0 0
+vertex 0 1 0
+endloop
+endfacet
+facet normal 0 0 1
+outer loop
+vertex 1.0001 0 0
+vertex 1 1 0
+vertex 0 0.9999 0
+endloop
+endfacet
+endsolid test
+";
+    let exact: super::HalfEdgeGraph<Data> =
+        stl::read(file.as_bytes(), stl::Welding::Exact).unwrap();
+    assert_eq!(exact.iter_vertices().count(), 6);
+
+    let welded: super::HalfEdgeGraph<Data> =
+        stl::read(file.as_bytes(), stl::Welding::Epsilon(0.001)).unwrap();
+    assert_eq!(welded.iter_vertices().count(), 4);
+    assert_eq!(welded.iter_edges().count(), 5);
+
+    // Everything is merged into a single point
+    let collapsed: super::HalfEdgeGraph<Data> =
+        stl::read(file.as_bytes(), stl::Welding::Epsilon(10.)).unwrap();
+    assert_eq!(collapsed.iter_vertices().count(), 1);
+    assert_eq!(collapsed.iter_faces().count(), 0);
+
+    // Huge coordinates are in the last cells, and can still be welded
+    let huge = file.replace("vertex 1 1 0", "vertex 1e300 -1e300 0");
+    let welded: super::HalfEdgeGraph<Data> =
+        stl::read(huge.as_bytes(), stl::Welding::Epsilon(1e-10)).unwrap();
+    assert_eq!(welded.iter_vertices().count(), 6);
+
+    let is_parse_error = |result: Result<super::HalfEdgeGraph<Data>, crate::io::ReadError>| {
+        matches!(result, Err(crate::io::ReadError::Parse(_)))
+    };
+    let nan = file.replace("vertex 1 1 0", "vertex 1 NaN 0");
+    assert!(is_parse_error(stl::read(
+        nan.as_bytes(),
+        stl::Welding::Exact
+    )));
+    let infinite = file.replace("vertex 1 1 0", "vertex 1 inf 0");
+    assert!(is_parse_error(stl::read(
+        infinite.as_bytes(),
+        stl::Welding::Epsilon(0.1)
+    )));
+    for &epsilon in &[0., -1., f64::NAN, f64::INFINITY] {
+        assert!(is_parse_error(stl::read(
+            file.as_bytes(),
+            stl::Welding::Epsilon(epsilon)
+        )));
+    }
+
+    // Huge counts in the header fails when the file runs out
+    let huge = format!("OFF\n{} {} 0\n0 0 0\n", usize::MAX, usize::MAX);
+    assert!(is_parse_error(off::read(huge.as_bytes())));
+}
+
+#[test]
+fn dot_and_graphml() {
+    use crate::io::ExportOptions;
+
+    #[derive(Default, Debug)]
+    struct Data;
+    impl crate::Data for Data {
+        type Face = ();
+        type Edge = u32;
+        type HalfEdge = ();
+        type Vertex = String;
+    }
+
+    let mut mesh = super::HalfEdgeGraph::<Data>::default();
+    let a = mesh.new_vertex("a".to_string());
+    let b = mesh.new_vertex("b & \"c\"".to_string());
+    let c = mesh.new_vertex("<c>".to_string());
+    let d = mesh.new_vertex("d".to_string());
+    mesh.new_face(&[a, b, c], ()).unwrap();
+    let e = mesh.new_edge(c, d, 7).unwrap();
+
+    let options = ExportOptions::<Data>::default()
+        .vertex_label(|vertex| vertex.to_string())
+        .edge_label(|edge| edge.to_string())
+        .face_clusters(true);
+    let mut dot = Vec::new();
+    mesh.to_dot(&mut dot, &options).unwrap();
+    let dot = String::from_utf8(dot).unwrap();
+    assert_eq!(dot.matches("subgraph cluster_f").count(), 1);
+    assert_eq!(dot.matches("dir=none").count(), 4);
+    assert!(dot.contains(r#"[label="b & \"c\""]"#));
+
+    let mut dot = Vec::new();
+    mesh.to_dot(&mut dot, &ExportOptions::default().half_edges(true))
+        .unwrap();
+    let dot = String::from_utf8(dot).unwrap();
+    assert_eq!(dot.matches("->").count(), 8);
+    assert_eq!(dot.matches("next h").count(), 8);
+
+    let mut graphml = Vec::new();
+    mesh.to_graphml(&mut graphml, &options).unwrap();
+    let read = super::HalfEdgeGraph::<Data>::from_graphml(
+        &graphml[..],
+        |attributes| attributes["label"].clone(),
+        |attributes| attributes["label"].parse().unwrap(),
+    )
+    .unwrap();
+    assert_eq!(read.iter_faces().count(), 0);
+    assert_eq!(read.iter_edges().count(), 4);
+    let mut labels: Vec<_> = read.iter_vertices().map(|v| v.to_string()).collect();
+    labels.sort();
+    assert_eq!(labels, vec!["<c>", "a", "b & \"c\"", "d"]);
+    let edge = read.iter_edges().find(|edge| **edge == 7).unwrap();
+    let [v1, v2] = edge.vertices();
+    assert_eq!((v1.as_str(), v2.as_str()), ("<c>", "d"));
+    assert_eq!(mesh[e], 7);
+
+    // Edges before the nodes, comments and keys without names
+    let file = r#"<?xml version="1.0"?>
+<graphml>
+  <!-- a <comment> -->
+  <key id="d0" for="node"/>
+  <graph edgedefault="undirected">
+    <edge source="n1" target="n0"/>
+    <node id="n0"><data key="d0">first</data></node>
+    <node id='n1'/>
+  </graph>
+</graphml>"#;
+    let read = super::HalfEdgeGraph::<Data>::from_graphml(
+        file.as_bytes(),
+        |attributes| attributes.get("d0").cloned().unwrap_or_default(),
+        |_| 0,
+    )
+    .unwrap();
+    let mut labels: Vec<_> = read.iter_vertices().map(|v| v.to_string()).collect();
+    labels.sort();
+    assert_eq!(labels, vec!["", "first"]);
+    assert_eq!(read.iter_edges().count(), 1);
+
+    let invalid = file.replace("target=\"n0\"", "target=\"n2\"");
+    assert!(super::HalfEdgeGraph::<Data>::from_graphml(
+        invalid.as_bytes(),
+        |_| String::new(),
+        |_| 0
+    )
+    .is_err());
+
+    // CDATA sections, character references and the defaults of keys
+    let file = r#"<graphml>
+  <key id="d0" for="node" attr.name="name"/>
+  <key id="d1" for="node" attr.name="color"><default> red </default></key>
+  <key id="d2" for="edge" attr.name="weight"><default>3</default></key>
+  <graph edgedefault="undirected">
+    <node id="n0"><data key="d0"><![CDATA[a > b & <c>]]></data></node>
+    <node id="n1"><data key="d0">&#38;&#x3C;<!-- x -->&lt;<![CDATA[&amp;]]></data><data key="d1">blue</data></node>
+    <edge source="n0" target="n1"/>
+  </graph>
+</graphml>"#;
+    let read = super::HalfEdgeGraph::<Data>::from_graphml(
+        file.as_bytes(),
+        |attributes| format!("{} {}", attributes["name"], attributes["color"]),
+        |attributes| attributes["weight"].parse().unwrap(),
+    )
+    .unwrap();
+    let mut labels: Vec<_> = read.iter_vertices().map(|v| v.to_string()).collect();
+    labels.sort();
+    assert_eq!(labels, vec!["&<<&amp; blue", "a > b & <c> red"]);
+    assert_eq!(*read.iter_edges().next().unwrap(), 3);
+
+    // Entities other than the predefined ones are not supported
+    for invalid in &[
+        file.replace("&#38;", "&nbsp;"),
+        file.replace("&#38;", "&#xD800;"),
+        file.replace(
+            "<graphml>",
+            "<!DOCTYPE graphml [<!ENTITY e \"x\">]><graphml>",
+        ),
+        file.replace("]]></data></node>", "</data></node>"),
+    ] {
+        assert!(matches!(
+            super::HalfEdgeGraph::<Data>::from_graphml(
+                invalid.as_bytes(),
+                |_| String::new(),
+                |_| 0
+            ),
+            Err(crate::io::ReadError::Parse(_))
+        ));
+    }
+}
+
+#[test]
+fn cytoscape_json() {
+    use crate::{debug, Handle};
+
+    #[derive(Default)]
+    struct Data;
+    impl crate::Data for Data {
+        type Face = i32;
+        type Edge = i32;
+        type HalfEdge = i32;
+        type Vertex = i32;
+    }
+
+    let mut mesh = super::HalfEdgeGraph::<Data>::default();
+    let v1 = mesh.new_vertex(1);
+    let v2 = mesh.new_vertex(2);
+    let v3 = mesh.new_vertex(3);
+    let v4 = mesh.new_vertex(4);
+    let face = mesh.new_face(&[v1, v2, v3], 5).unwrap();
+
+    let mut json = Vec::new();
+    debug::write_cytoscape(&mesh, &mut json).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    assert!(json.starts_with('{'));
+    assert!(json.contains(&format!(
+        "\"{:?}\": {{\"hedge\": null, \"label\": \"4\"}}",
+        v4
+    )));
+    assert!(json.contains("\"label\": \"5\""));
+    assert_eq!(json.matches("\"pair\"").count(), 6);
+    assert!(!json.contains(",\n  }"));
+
+    let mut json = Vec::new();
+    debug::write_cytoscape_with(&mesh, &mut json, |handle| match handle {
+        Handle::Face(handle) if handle == face => Some("\"outer\"\n".to_string()),
+        _ => None,
+    })
+    .unwrap();
+    let json = String::from_utf8(json).unwrap();
+    assert_eq!(json.matches("\"label\"").count(), 1);
+    assert!(json.contains(r#""label": "\"outer\"\n""#));
+}
+
+#[test]
+fn render_svg() {
+    use crate::render::{self, SvgOptions};
+
+    #[derive(Default)]
+    struct Data;
+    impl crate::Data for Data {
+        type Face = ();
+        type Edge = ();
+        type HalfEdge = ();
+        type Vertex = [f64; 2];
+    }
+
+    let mut mesh = super::HalfEdgeGraph::<Data>::default();
+    let v1 = mesh.new_vertex([0., 0.]);
+    let v2 = mesh.new_vertex([2., 0.]);
+    let v3 = mesh.new_vertex([0., 2.]);
+    let v4 = mesh.new_vertex([2., 2.]);
+    let face = mesh.new_face(&[v1, v2, v3], ()).unwrap();
+    let e = mesh.new_edge(v2, v4, ()).unwrap();
+
+    let svg = render::svg(&mesh, |vertex| *vertex, &SvgOptions::default());
+    assert!(svg.contains(r#"viewBox="-0.1 -2.1 2.2 2.2""#));
+    assert_eq!(svg.matches("<polygon").count(), 1);
+    assert_eq!(svg.matches("<path").count(), 4);
+    assert_eq!(svg.matches("<circle").count(), 4);
+    assert!(!svg.contains("<text"));
+    assert!(!svg.contains("#e22"));
+
+    let options = SvgOptions::default()
+        .half_edges(true)
+        .labels(true)
+        .highlight(vec![v4.into(), e.into(), face.into()]);
+    let svg = render::svg(&mesh, |vertex| *vertex, &options);
+    assert_eq!(svg.matches("marker-end").count(), 8);
+    assert_eq!(svg.matches("<text").count(), 1 + 4 + 8 + 4);
+    assert!(svg.contains(&format!(">{:?}</text>", v4)));
+    assert!(svg.contains("#fc8"));
+    // The arrow marker, the edge, the vertex and the labels of all 3
+    assert_eq!(svg.matches("\"#e22\"").count(), 6);
+}
+
+#[test]
+fn validate_broken_links() {
+    use crate::InvariantError;
+
+    let mut mesh = HalfEdgeGraph::default();
+    let v1 = mesh.new_vertex(());
+    let v2 = mesh.new_vertex(());
+    let v3 = mesh.new_vertex(());
+    let face = mesh.new_face(&[v1, v2, v3], ()).unwrap();
+    assert_eq!(mesh.validate(), Ok(()));
+
+    let h1 = mesh.find_half_edge(v1, v2).unwrap();
+    let h2 = mesh.find_half_edge(v2, v3).unwrap();
+
+    let mut broken = mesh.clone();
+    broken.half_edges[h1].next = h1;
+    assert_eq!(broken.validate(), Err(InvariantError::Next(h1)));
+
+    let mut broken = mesh.clone();
+    broken.half_edges[h2].face = FaceHandle::null();
+    assert!(matches!(broken.validate(), Err(InvariantError::Face(_))));
+
+    let mut broken = mesh.clone();
+    broken.vertices[v1].hedge = mesh.find_half_edge(v2, v1).unwrap();
+    assert_eq!(broken.validate(), Err(InvariantError::VertexHedge(v1)));
+
+    let mut broken = mesh.clone();
+    broken.faces.remove(face);
+    assert_eq!(broken.validate(), Err(InvariantError::Dangling(h1.into())));
+}
+
+#[test]
+fn face_visiting_vertex_twice() {
+    let mut mesh = HalfEdgeGraph::default();
+    let v: Vec<_> = (0..5).map(|_| mesh.new_vertex(())).collect();
+
+    // Two edges sticking into the face from v0
+    let face = mesh.new_face(&[v[0], v[1], v[0], v[2]], ()).unwrap();
+    assert_eq!(mesh.face(face).unwrap().vertices().count(), 4);
+
+    // The face would surround all of v0, but the edge to v3 is outside of it
+    let mut mesh = HalfEdgeGraph::default();
+    let v: Vec<_> = (0..5).map(|_| mesh.new_vertex(())).collect();
+    mesh.new_edge(v[0], v[3], ()).unwrap();
+    mesh.new_edge(v[0], v[4], ()).unwrap();
+    assert_eq!(mesh.new_face(&[v[0], v[1], v[0], v[2]], ()), None);
+    mesh.verify_invarians();
+}
+
+#[cfg(feature = "proptest")]
+proptest::proptest! {
+    #[test]
+    fn proptest_valid_graphs(
+        triangulation in crate::proptest::planar_triangulation::<()>(1..30),
+        mesh in crate::proptest::polygon_mesh::<()>(1..5, 1..5),
+        graph in crate::proptest::edge_graph::<()>(0..10, 0..20),
+    ) {
+        for graph in &[triangulation, mesh, graph] {
+            proptest::prop_assert_eq!(graph.validate(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn proptest_operations(
+        mesh in crate::proptest::polygon_mesh::<()>(1..4, 1..4),
+        operations in crate::proptest::operations(0..40),
+    ) {
+        let mut mesh = mesh;
+        for operation in &operations {
+            operation.apply(&mut mesh);
+            proptest::prop_assert_eq!(mesh.validate(), Ok(()), "after {:?}", operation);
+        }
+    }
+}
+
+#[test]
+fn primitives() {
+    use crate::primitives;
+
+    #[derive(Default)]
+    struct Data;
+    impl crate::Data for Data {
+        type Face = ();
+        type Edge = ();
+        type HalfEdge = ();
+        type Vertex = [f64; 3];
+    }
+    type Mesh = super::HalfEdgeGraph<Data>;
+
+    // Returns the vertex, edge & face counts, and the signed volume of the faces
+    fn measure(mesh: &Mesh) -> ([usize; 3], f64) {
+        mesh.verify_invarians();
+        let volume = mesh
+            .iter_faces()
+            .map(|face| {
+                let p: Vec<[f64; 3]> = face.vertices().map(|v| *v).collect();
+                (1..p.len() - 1)
+                    .map(|i| {
+                        let [a, b, c] = [p[0], p[i], p[i + 1]];
+                        (a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+                            + a[2] * (b[0] * c[1] - b[1] * c[0]))
+                            / 6.
+                    })
+                    .sum::<f64>()
+            })
+            .sum();
+        let counts = [
+            mesh.iter_vertices().count(),
+            mesh.iter_edges().count(),
+            mesh.iter_faces().count(),
+        ];
+        (counts, volume)
+    }
+    let position = |p| p;
+
+    let (counts, _) = measure(&primitives::grid(2, 3, position));
+    assert_eq!(counts, [12, 17, 6]);
+    let (counts, _) = measure(&primitives::disc(6, position));
+    assert_eq!(counts, [7, 12, 6]);
+
+    let (counts, volume) = measure(&primitives::cube(position));
+    assert_eq!(counts, [8, 12, 6]);
+    assert!((volume - 8.).abs() < 1e-9);
+
+    let (counts, volume) = measure(&primitives::prism(5, position));
+    assert_eq!(counts, [10, 15, 7]);
+    assert!(volume > 0.);
+
+    let (counts, volume) = measure(&primitives::cylinder(8, 3, position));
+    assert_eq!(counts, [34, 8 * 4 + 8 * 3 + 8 * 2, 8 * 3 + 16]);
+    assert!(volume > 0.);
+
+    let (counts, volume) = measure(&primitives::cone(8, position));
+    assert_eq!(counts, [10, 24, 16]);
+    assert!(volume > 0.);
+
+    let (counts, volume) = measure(&primitives::uv_sphere(8, 4, position));
+    assert_eq!(counts, [26, 56, 32]);
+    assert!(volume > 0.);
+
+    let (counts, volume) = measure(&primitives::icosphere(2, position));
+    assert_eq!(counts, [162, 480, 320]);
+    assert!((volume - 4. / 3. * std::f64::consts::PI).abs() < 0.2);
+
+    let (counts, volume) = measure(&primitives::torus(8, 6, 2., 0.5, position));
+    assert_eq!(counts, [48, 96, 48]);
+    assert!(volume > 0.);
+}
@@ -0,0 +1,159 @@
+//! Updating the data of every item, from the data of its neighbors
+//!
+//! This is a common pattern (smoothing, diffusion etc) that is hard to do with the *FnMut types,
+//! since the neighbors can't be read while the center is borrowed mutably.
+//!
+//! There is 2 variants of each update:
+//!  - `update_*` computes all new values from the old state of the graph (double buffered, Jacobi style),
+//!    the closure is given a copy of the old value to modify.
+//!  - `update_*_in_place` writes each new value directly (Gauss-Seidel style), so items visited later
+//!    see the new values of the items before them.
+use crate::{Data, EdgeFn, FaceFn, HalfEdgeGraph, VertexFn};
+
+/// Read only view of the neighbors of a item, see [HalfEdgeGraph::update_vertices]
+///
+/// `Center` is the *Fn type of the item being updated, which can be used to read anything in the graph.
+pub struct NeighborView<Center> {
+    center: Center,
+}
+
+impl<Center> NeighborView<Center> {
+    fn new(center: Center) -> Self {
+        Self { center }
+    }
+
+    /// The item being updated.
+    ///
+    /// Note that for the in place updates, the data of the center is not available while it is updated,
+    /// it is the default value if read through the graph or [NeighborView::iter].
+    pub fn center(&self) -> &Center {
+        &self.center
+    }
+}
+
+impl<'graph, DataTypes: Data> NeighborView<VertexFn<'graph, DataTypes>> {
+    /// The data of all vertices connected to the center with a edge
+    pub fn iter(&self) -> impl Iterator<Item = &'graph DataTypes::Vertex> + 'graph {
+        self.center
+            .vertices()
+            .map(|vertex| &vertex.graph.vertices[vertex.handle].data)
+    }
+
+    pub fn len(&self) -> usize {
+        self.center.vertices().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.center.hedge().is_none()
+    }
+}
+
+impl<'graph, DataTypes: Data> NeighborView<FaceFn<'graph, DataTypes>> {
+    /// The data of all faces sharing a edge with the center
+    pub fn iter(&self) -> impl Iterator<Item = &'graph DataTypes::Face> + 'graph {
+        self.center
+            .faces()
+            .map(|face| &face.graph.faces[face.handle].data)
+    }
+
+    pub fn len(&self) -> usize {
+        self.center.faces().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<'graph, DataTypes: Data> NeighborView<EdgeFn<'graph, DataTypes>> {
+    /// The data of all edges sharing a vertex with the center
+    pub fn iter(&self) -> impl Iterator<Item = &'graph DataTypes::Edge> + 'graph {
+        let center = self.center.handle;
+        let [v1, v2] = self.center.vertices();
+
+        v1.edges()
+            .chain(v2.edges())
+            .filter(move |edge| edge.handle != center)
+            .map(|edge| &edge.graph.edges[edge.handle].data)
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+macro_rules! update {
+    (
+        $(#[$meta:meta])*
+        fn $name:ident, $name_in_place:ident -> $type:ident, $fn_type:ident;
+        map: $map:ident;
+    ) => {
+        $(#[$meta])*
+        pub fn $name<F>(&mut self, mut f: F)
+        where
+            DataTypes::$type: Clone,
+            F: FnMut(&mut DataTypes::$type, NeighborView<$fn_type<'_, DataTypes>>),
+        {
+            let values: Vec<_> = self
+                .$map
+                .iter()
+                .map(|(handle, item)| {
+                    let mut value = item.data.clone();
+                    f(&mut value, NeighborView::new($fn_type::new(self, handle)));
+                    (handle, value)
+                })
+                .collect();
+
+            for (handle, value) in values {
+                self.record_data(handle);
+                self.$map[handle].data = value;
+            }
+        }
+
+        $(#[$meta])*
+        ///
+        /// Gauss-Seidel variant, the new values are written directly so items are updated
+        /// using the new values of the items updated before them.
+        ///
+        /// The data of the item is taken out (replaced by the default value) while it is updated,
+        /// so if the item is its own neighbor, like a vertex with a self loop, the neighbors
+        /// see the default value instead of the old value.
+        pub fn $name_in_place<F>(&mut self, mut f: F)
+        where
+            F: FnMut(&mut DataTypes::$type, NeighborView<$fn_type<'_, DataTypes>>),
+        {
+            let handles: Vec<_> = self.$map.keys().collect();
+            for handle in handles {
+                self.record_data(handle);
+
+                let mut value = std::mem::take(&mut self.$map[handle].data);
+                f(&mut value, NeighborView::new($fn_type::new(self, handle)));
+                self.$map[handle].data = value;
+            }
+        }
+    };
+}
+
+impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
+    update!(
+        /// Updates the data of every vertex, from the data of the connected vertices
+        fn update_vertices, update_vertices_in_place -> Vertex, VertexFn;
+        map: vertices;
+    );
+
+    update!(
+        /// Updates the data of every face, from the data of the adjacent faces
+        fn update_faces, update_faces_in_place -> Face, FaceFn;
+        map: faces;
+    );
+
+    update!(
+        /// Updates the data of every edge, from the data of the edges sharing a vertex with it
+        fn update_edges, update_edges_in_place -> Edge, EdgeFn;
+        map: edges;
+    );
+}
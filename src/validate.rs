@@ -0,0 +1,171 @@
+//! Checking of the invariants of the links between the items
+use std::collections::HashMap;
+
+use crate::{Data, EdgeHandle, FaceHandle, HalfEdgeGraph, HalfEdgeHandle, Handle, VertexHandle};
+
+/// Broken invariants found by [HalfEdgeGraph::validate]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvariantError {
+    /// The item links to a item that doesn't exist
+    Dangling(Handle),
+    /// The pair of the half edge is the half edge itself, or doesn't have it as its pair
+    Pair(HalfEdgeHandle),
+    /// The half edge isn't the `prev` of its `next`
+    Next(HalfEdgeHandle),
+    /// The half edge doesn't end at the vertex its `next` starts at
+    Chain(HalfEdgeHandle),
+    /// The half edge and its pair have different edges
+    Edge(HalfEdgeHandle),
+    /// The half edge and its `next` have different faces
+    Face(HalfEdgeHandle),
+    /// The half edge starts and ends at the same vertex, and [Data::ALLOW_SELF_LOOPS] is false
+    SelfLoop(HalfEdgeHandle),
+    /// The `hedge` of the vertex doesn't start at it, or is null while half edges starts at it
+    VertexHedge(VertexHandle),
+    /// Not all half edges that starts at the vertex are reached when rotating around it
+    Fan(VertexHandle),
+    /// The `hedge` of the edge doesn't have the edge
+    EdgeHedge(EdgeHandle),
+    /// The `hedge` of the face doesn't have the face
+    FaceHedge(FaceHandle),
+}
+
+impl std::fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvariantError::Dangling(handle) => {
+                write!(f, "{:?} links to a item that doesn't exist", handle)
+            }
+            InvariantError::Pair(hedge) => write!(f, "pair of half edge {:?} is broken", hedge),
+            InvariantError::Next(hedge) => {
+                write!(f, "half edge {:?} isn't the prev of its next", hedge)
+            }
+            InvariantError::Chain(hedge) => {
+                write!(f, "half edge {:?} doesn't end where its next starts", hedge)
+            }
+            InvariantError::Edge(hedge) => {
+                write!(f, "half edge {:?} and its pair have different edges", hedge)
+            }
+            InvariantError::Face(hedge) => {
+                write!(f, "half edge {:?} and its next have different faces", hedge)
+            }
+            InvariantError::SelfLoop(hedge) => write!(f, "half edge {:?} is a self loop", hedge),
+            InvariantError::VertexHedge(vertex) => {
+                write!(f, "hedge of vertex {:?} is broken", vertex)
+            }
+            InvariantError::Fan(vertex) => {
+                write!(f, "not all half edges of vertex {:?} are linked", vertex)
+            }
+            InvariantError::EdgeHedge(edge) => write!(f, "hedge of edge {:?} is broken", edge),
+            InvariantError::FaceHedge(face) => write!(f, "hedge of face {:?} is broken", face),
+        }
+    }
+}
+
+impl std::error::Error for InvariantError {}
+
+impl<DataTypes: Data> HalfEdgeGraph<DataTypes> {
+    /// Checks that all links between the items are consistent, returns the first broken invariant found.
+    ///
+    /// Parallel edges are not reported even if [Data::ALLOW_PARALLEL_EDGES] is false, since
+    /// [HalfEdgeGraph::add_polygons] can glue edges together with them.
+    /// This walks all items, so it is meant for tests and debugging.
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        // Check that all links exists first, so the rest can index without checking
+        for (handle, hedge) in &self.half_edges {
+            let dangling = !self.half_edges.contains_key(hedge.pair)
+                || !self.half_edges.contains_key(hedge.next)
+                || !self.half_edges.contains_key(hedge.prev)
+                || !self.vertices.contains_key(hedge.vertex)
+                || !self.edges.contains_key(hedge.edge)
+                || !(hedge.face.is_null() || self.faces.contains_key(hedge.face));
+            if dangling {
+                return Err(InvariantError::Dangling(handle.into()));
+            }
+        }
+        for (handle, vertex) in &self.vertices {
+            if !(vertex.hedge.is_null() || self.half_edges.contains_key(vertex.hedge)) {
+                return Err(InvariantError::Dangling(handle.into()));
+            }
+        }
+        for (handle, edge) in &self.edges {
+            if !self.half_edges.contains_key(edge.hedge) {
+                return Err(InvariantError::Dangling(handle.into()));
+            }
+        }
+        for (handle, face) in &self.faces {
+            if !self.half_edges.contains_key(face.hedge) {
+                return Err(InvariantError::Dangling(handle.into()));
+            }
+        }
+
+        let tail = |hedge: HalfEdgeHandle| self.half_edges[self.half_edges[hedge].pair].vertex;
+
+        let mut out_count = HashMap::<VertexHandle, usize>::new();
+        for (handle, hedge) in &self.half_edges {
+            let pair = &self.half_edges[hedge.pair];
+            let next = &self.half_edges[hedge.next];
+            if hedge.pair == handle || pair.pair != handle {
+                return Err(InvariantError::Pair(handle));
+            }
+            if next.prev != handle {
+                return Err(InvariantError::Next(handle));
+            }
+            if tail(hedge.next) != hedge.vertex {
+                return Err(InvariantError::Chain(handle));
+            }
+            if pair.edge != hedge.edge {
+                return Err(InvariantError::Edge(handle));
+            }
+            if next.face != hedge.face {
+                return Err(InvariantError::Face(handle));
+            }
+
+            if pair.vertex == hedge.vertex && !DataTypes::ALLOW_SELF_LOOPS {
+                return Err(InvariantError::SelfLoop(handle));
+            }
+            *out_count.entry(pair.vertex).or_default() += 1;
+        }
+
+        for (handle, vertex) in &self.vertices {
+            let count = out_count.get(&handle).copied().unwrap_or(0);
+            if vertex.hedge.is_null() {
+                if count > 0 {
+                    return Err(InvariantError::VertexHedge(handle));
+                }
+                continue;
+            }
+            if tail(vertex.hedge) != handle {
+                return Err(InvariantError::VertexHedge(handle));
+            }
+
+            // Rotate around the vertex, all half edges have been checked above so this
+            // will return to the start, but limit it in case the fan is broken
+            let mut fan = 0;
+            let mut current = vertex.hedge;
+            loop {
+                fan += 1;
+                current = self.half_edges[self.half_edges[current].pair].next;
+                if current == vertex.hedge || fan > count {
+                    break;
+                }
+            }
+            if fan != count {
+                return Err(InvariantError::Fan(handle));
+            }
+        }
+
+        for (handle, edge) in &self.edges {
+            if self.half_edges[edge.hedge].edge != handle {
+                return Err(InvariantError::EdgeHedge(handle));
+            }
+        }
+        for (handle, face) in &self.faces {
+            if self.half_edges[face.hedge].face != handle {
+                return Err(InvariantError::FaceHedge(handle));
+            }
+        }
+
+        Ok(())
+    }
+}
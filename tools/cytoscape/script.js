@@ -6,7 +6,8 @@ let style = [
         selector: 'node',
         style: {
             'background-color': '#666',
-            'label': 'data(id)',
+            'label': 'data(label)',
+            'text-wrap': 'wrap',
             'font-size': '8px',
             'width': '12px',
             'height': '12px',
@@ -27,7 +28,7 @@ let style = [
         selector: '[class="vertex"]',
         style: {
             'background-color': '#F00',
-            'label': 'data(id)',
+            'label': 'data(label)',
             'width': '20px',
             'height': '20px',
             'font-size': '12px',
@@ -36,14 +37,14 @@ let style = [
     {
         selector: '[class="edge"]',
         style: {
-            'label': 'data(id)',
+            'label': 'data(label)',
         }
     },
     {
         selector: '[class="half_edge"]',
         style: {
             'background-color': '#00F',
-            'label': 'data(id)',
+            'label': 'data(label)',
         }
     },
     {
@@ -107,6 +108,11 @@ let style = [
 ];
 
 
+// The handle of a item, followed by its data label if there is one
+function label(handle, item) {
+    return item.label === undefined ? handle : handle + '\n' + item.label;
+}
+
 var cy = cytoscape({
     container: $('#graph'),
     style: style,
@@ -120,6 +126,7 @@ for(let handle in data.vertices) {
         group: 'nodes',
         data: {
             id: handle,
+            label: label(handle, data.vertices[handle]),
             class: 'vertex'
         }
     });
@@ -164,6 +171,7 @@ for(let handle in data.half_edges) {
         group: 'nodes',
         data: {
             id: handle,
+            label: label(handle, data.half_edges[handle]),
             class: 'half_edge',
         }
     });
@@ -176,6 +184,7 @@ if (SHOW_EDGES) {
             group: 'nodes',
             data: {
                 id: handle,
+                label: label(handle, data.edges[handle]),
                 class: 'edge',
             }
         });
@@ -188,6 +197,7 @@ for(let handle in data.faces) {
         group: 'nodes',
         data: {
             id: handle,
+            label: label(handle, data.faces[handle]),
             class: 'face',
         }
     });